@@ -0,0 +1,281 @@
+use crate::library::Album;
+use crate::track::Track;
+use anyhow::Result;
+use chrono::DateTime;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags, Row};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// Columns read from the beets `albums` table
+const ALBUM_COLUMNS: &str =
+    "id, album, albumartist, year, genre, disctotal, albumtype, mb_albumid, label, country";
+
+// Columns read from the beets `items` table, in `Track` field order
+const ITEM_COLUMNS: &str = "id, title, artist, album, albumartist, year, genre, length, \
+     track, tracktotal, disc, disctotal, bitrate, format, path, added, comments, bpm, \
+     composer, label, country, albumtype, mb_trackid, mb_albumid, mb_artistid, album_id";
+
+/// Read-only view of a beets `library.db`.
+pub struct BeetsDb {
+    conn: Connection,
+    albums: BTreeMap<String, Album>,
+}
+
+impl BeetsDb {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| anyhow::anyhow!("Failed to open beets database '{}': {}", path, e))?;
+
+        let mut db = Self {
+            conn,
+            albums: BTreeMap::new(),
+        };
+        db.albums = db.load_albums()?;
+        Ok(db)
+    }
+
+    /// Albums from the `albums` table, without tracks.
+    pub fn albums(&self) -> impl Iterator<Item = &Album> {
+        self.albums.values()
+    }
+
+    /// Every item in the library as a `Track`.
+    ///
+    /// Album-level fields of items that belong to an album are taken from
+    /// the `albums` table, so a stale per-item copy can't split an album.
+    pub fn tracks(&self) -> Result<Vec<Track>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM items ORDER BY id", ITEM_COLUMNS))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Track {
+                id: id(row, 0)?,
+                title: text(row, 1)?,
+                artist: text(row, 2)?,
+                album: text(row, 3)?,
+                albumartist: text(row, 4)?,
+                year: count(row, 5)?,
+                genre: text(row, 6)?,
+                length: length(row, 7)?,
+                track: count(row, 8)?,
+                tracktotal: count(row, 9)?,
+                disc: count(row, 10)?,
+                disctotal: count(row, 11)?,
+                bitrate: bitrate(row, 12)?,
+                format: text(row, 13)?,
+                path: text(row, 14)?,
+                added: timestamp(row, 15)?,
+                comments: text(row, 16)?,
+                bpm: count(row, 17)?,
+                composer: text(row, 18)?,
+                label: text(row, 19)?,
+                country: text(row, 20)?,
+                albumtype: text(row, 21)?,
+                mb_trackid: text(row, 22)?,
+                mb_albumid: text(row, 23)?,
+                mb_artistid: text(row, 24)?,
+                album_id: id(row, 25)?,
+            })
+        })?;
+
+        let mut tracks = Vec::new();
+        for row in rows {
+            let mut track = row?;
+            if let Some(album) = self.albums.get(&track.album_id) {
+                track.album = album.title.clone();
+                track.albumartist = album.artist.clone();
+                track.albumtype = album.albumtype.clone();
+                track.mb_albumid = album.mb_albumid.clone();
+                track.label = album.label.clone();
+                track.country = album.country.clone();
+            }
+            tracks.push(track);
+        }
+
+        Ok(tracks)
+    }
+
+    fn load_albums(&self) -> Result<BTreeMap<String, Album>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM albums", ALBUM_COLUMNS))?;
+
+        let rows = stmt.query_map([], |row| {
+            let mut album = Album::new(id(row, 0)?, text(row, 1)?, text(row, 2)?);
+            album.year = count(row, 3)?;
+            album.genre = text(row, 4)?;
+            album.disctotal = count(row, 5)?.unwrap_or(1);
+            album.albumtype = text(row, 6)?;
+            album.mb_albumid = text(row, 7)?;
+            album.label = text(row, 8)?;
+            album.country = text(row, 9)?;
+            Ok(album)
+        })?;
+
+        let mut albums = BTreeMap::new();
+        for row in rows {
+            let album = row?;
+            albums.insert(album.id.clone(), album);
+        }
+
+        Ok(albums)
+    }
+}
+
+// beets columns are loosely typed, so read everything through `Value`
+fn text(row: &Row, idx: usize) -> rusqlite::Result<String> {
+    Ok(match row.get::<_, Value>(idx)? {
+        Value::Null => String::new(),
+        Value::Integer(i) => i.to_string(),
+        Value::Real(f) => f.to_string(),
+        Value::Text(s) => s,
+        Value::Blob(b) => String::from_utf8_lossy(&b).into_owned(),
+    })
+}
+
+// Row ids as strings, empty when NULL
+fn id(row: &Row, idx: usize) -> rusqlite::Result<String> {
+    Ok(row
+        .get::<_, Option<i64>>(idx)?
+        .map(|id| id.to_string())
+        .unwrap_or_default())
+}
+
+// Integer fields where beets stores 0 for "unset"
+fn count(row: &Row, idx: usize) -> rusqlite::Result<Option<u32>> {
+    Ok(match row.get::<_, Value>(idx)? {
+        Value::Integer(n) => u32::try_from(n).ok().filter(|&n| n > 0),
+        Value::Real(f) if f >= 1.0 => Some(f as u32),
+        Value::Text(s) => s.trim().parse().ok().filter(|&n| n > 0),
+        _ => None,
+    })
+}
+
+fn length(row: &Row, idx: usize) -> rusqlite::Result<Option<Duration>> {
+    Ok(row
+        .get::<_, Option<f64>>(idx)?
+        .filter(|&seconds| seconds > 0.0)
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok()))
+}
+
+// beets stores bits per second; tracks carry kbps
+fn bitrate(row: &Row, idx: usize) -> rusqlite::Result<Option<u32>> {
+    Ok(row
+        .get::<_, Option<f64>>(idx)?
+        .filter(|&bps| bps > 0.0)
+        .map(|bps| (bps / 1000.0).round() as u32))
+}
+
+// Unset (NULL or 0) timestamps are empty rather than the Unix epoch. Set
+// ones are in UTC, so pages don't depend on the building machine's timezone
+fn timestamp(row: &Row, idx: usize) -> rusqlite::Result<String> {
+    let Some(seconds) = row.get::<_, Option<f64>>(idx)?.filter(|&s| s > 0.0) else {
+        return Ok(String::new());
+    };
+    Ok(DateTime::from_timestamp(seconds as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::testing::TestSite;
+    use crate::parser::Parser;
+
+    // A beets library with only the columns we read, loosely typed like
+    // beets' own
+    fn library_db(path: &std::path::Path) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(&format!(
+            "
+            CREATE TABLE albums (id INTEGER PRIMARY KEY, {album_columns});
+            CREATE TABLE items (id INTEGER PRIMARY KEY, {item_columns});
+            INSERT INTO albums (id, album, albumartist, year, genre, disctotal, albumtype,
+                mb_albumid, label, country)
+            VALUES
+                (1, 'For Long Tomorrow', 'toe', 2009, 'Post-Rock', 1, 'album', 'mb-1',
+                    'Machupicchu Industrias', 'JP'),
+                (2, 'Ambient', 'Lamp', NULL, NULL, NULL, NULL, NULL, NULL, NULL);
+            INSERT INTO items (id, title, artist, album, albumartist, year, genre, length,
+                track, tracktotal, disc, disctotal, bitrate, format, path, added, comments,
+                bpm, composer, label, country, albumtype, mb_trackid, mb_albumid, mb_artistid,
+                album_id)
+            VALUES
+                (10, 'Goodbye', 'toe', 'stale title', 'toe', 2009, 'Math Rock', 253.4,
+                    3, 12, 1, 1, 320000.0, 'MP3', '/music/goodbye.mp3', 1700000000.0, '',
+                    '120', 'Takaaki Mino', 'old label', '', '', 'mb-t', 'mb-old', 'mb-a', 1),
+                (11, 'Yoru', 'Lamp', 'Ambient', 'Lamp', 0, NULL, 1e300,
+                    NULL, '', NULL, NULL, NULL, NULL, NULL, NULL, NULL,
+                    'fast', NULL, NULL, NULL, NULL, NULL, NULL, NULL, 2),
+                (12, 'Demo', 'Lamp', '', '', 2004, 'Pop', 180.0,
+                    0, 0, 0, 0, 0, 'FLAC', '/music/demo.flac', NULL, NULL,
+                    NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL);
+            ",
+            album_columns = ALBUM_COLUMNS.strip_prefix("id, ").unwrap(),
+            item_columns = ITEM_COLUMNS.strip_prefix("id, ").unwrap(),
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn reads_items_with_album_fields_from_albums() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.db");
+        library_db(&path);
+
+        let db = BeetsDb::open(path.to_str().unwrap()).unwrap();
+        let albums: Vec<(&str, Option<u32>, u32)> = db
+            .albums()
+            .map(|album| (album.title.as_str(), album.year, album.disctotal))
+            .collect();
+        assert_eq!(
+            albums,
+            [("For Long Tomorrow", Some(2009), 1), ("Ambient", None, 1)]
+        );
+
+        let tracks = db.tracks().unwrap();
+        let goodbye = &tracks[0];
+        assert_eq!(goodbye.id, "10");
+        assert_eq!(goodbye.album, "For Long Tomorrow");
+        assert_eq!(goodbye.label, "Machupicchu Industrias");
+        assert_eq!(goodbye.mb_albumid, "mb-1");
+        // Genre is per track in beets, so the item's own is kept
+        assert_eq!(goodbye.genre, "Math Rock");
+        assert_eq!((goodbye.track, goodbye.tracktotal), (Some(3), Some(12)));
+        assert_eq!(goodbye.length_display(), "4:13");
+        assert_eq!(goodbye.bitrate, Some(320));
+        assert_eq!(goodbye.bpm, Some(120));
+        assert_eq!(goodbye.added, "2023-11-14 22:13:20");
+
+        // NULLs and unparseable values are unset, not errors
+        let yoru = &tracks[1];
+        assert_eq!((yoru.year, yoru.length, yoru.bitrate), (None, None, None));
+        assert_eq!((yoru.tracktotal, yoru.bpm), (None, None));
+        assert_eq!((yoru.format.as_str(), yoru.added.as_str()), ("", ""));
+
+        let demo = &tracks[2];
+        assert_eq!(demo.album_id, "");
+        assert_eq!((demo.track, demo.bitrate), (None, None));
+    }
+
+    #[tokio::test]
+    async fn links_album_genre_only_when_its_tracks_have_it() {
+        let site = TestSite::new();
+        let path = site.root().join("library.db");
+        library_db(&path);
+        let library = Parser::new()
+            .parse_beets_db(path.to_str().unwrap())
+            .await
+            .unwrap();
+
+        site.generate(&library).await;
+
+        let album = site.read("albums/for-long-tomorrow.md");
+        assert!(album.contains("**Genre:** Post-Rock\n"), "{}", album);
+        assert!(!site.path("genres/post-rock.md").exists());
+        assert!(site.path("genres/math-rock.md").exists());
+    }
+}
@@ -0,0 +1,193 @@
+//! A local stand-in for the Steam Web API, so the games pipeline can be
+//! tested without network access or a real API key.
+
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tiny_http::{Header, Response, Server};
+
+/// A canned response for requests whose path starts with `path`.
+pub struct Route {
+    pub path: String,
+    pub status: u16,
+    pub body: String,
+    // How many requests this route answers before falling through
+    pub times: Option<usize>,
+}
+
+impl Route {
+    pub fn json(path: impl Into<String>, body: serde_json::Value) -> Self {
+        Self {
+            path: path.into(),
+            status: 200,
+            body: body.to_string(),
+            times: None,
+        }
+    }
+
+    pub fn status(path: impl Into<String>, status: u16, body: &str) -> Self {
+        Self {
+            path: path.into(),
+            status,
+            body: body.to_string(),
+            times: None,
+        }
+    }
+
+    /// Only answer the first `n` matching requests, so later routes for the
+    /// same path can take over (e.g. two 503s, then a 200).
+    pub fn times(mut self, n: usize) -> Self {
+        self.times = Some(n);
+        self
+    }
+}
+
+/// HTTP server on a random local port answering from a route table, first
+/// match wins. Unknown paths get a 404. Stops when dropped.
+pub struct FakeSteam {
+    server: Arc<Server>,
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FakeSteam {
+    pub fn start(mut routes: Vec<Route>) -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let url = format!("http://{}", server.server_addr());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let handle = {
+            let server = Arc::clone(&server);
+            let requests = Arc::clone(&requests);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    let path = request.url().to_string();
+                    requests.lock().unwrap().push(path.clone());
+
+                    let route = routes
+                        .iter_mut()
+                        .find(|r| path.starts_with(&r.path) && r.times != Some(0));
+                    let (status, body) = match route {
+                        Some(route) => {
+                            route.times = route.times.map(|n| n - 1);
+                            (route.status, route.body.clone())
+                        }
+                        None => (404, "Not Found".to_string()),
+                    };
+
+                    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(header);
+                    let _ = request.respond(response);
+                }
+            })
+        };
+
+        Self {
+            server,
+            url,
+            requests,
+            handle: Some(handle),
+        }
+    }
+
+    /// Base URL to hand to `SteamClient::with_api_url`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Path and query of every request served so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for FakeSteam {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub const OWNED_GAMES: &str = "/IPlayerService/GetOwnedGames/v1";
+
+/// A `GetOwnedGames` body for two games, shaped like the real API's.
+pub fn owned_games() -> serde_json::Value {
+    serde_json::json!({
+        "response": {
+            "game_count": 2,
+            "games": [
+                {
+                    "appid": 413150,
+                    "name": "Stardew Valley",
+                    "playtime_forever": 5400,
+                    "img_icon_url": "35d12f0e1c1b8c9e0a3f3b7d0c3e3c8e1f0f6c1a",
+                    "rtime_last_played": 1700000000,
+                    "playtime_windows_forever": 3000,
+                    "playtime_linux_forever": 2400,
+                    "playtime_deck_forever": 1800
+                },
+                {
+                    "appid": 620,
+                    "name": "Portal 2",
+                    "playtime_forever": 0,
+                    "img_icon_url": "",
+                    "rtime_last_played": 0
+                }
+            ]
+        }
+    })
+}
+
+pub const PLAYER_ACHIEVEMENTS: &str = "/ISteamUserStats/GetPlayerAchievements/v1";
+
+/// A `GetPlayerAchievements` body with two of three achievements unlocked.
+pub fn player_achievements() -> serde_json::Value {
+    serde_json::json!({
+        "playerstats": {
+            "steamID": "76561197960287930",
+            "gameName": "Stardew Valley",
+            "achievements": [
+                { "apiname": "Achievement_Greenhorn", "achieved": 1, "unlocktime": 1690000000, "name": "Greenhorn", "description": "Earn 15,000g" },
+                { "apiname": "Achievement_Cowpoke", "achieved": 1, "unlocktime": 1695000000, "name": "Cowpoke", "description": "Earn 50,000g" },
+                { "apiname": "Achievement_Legend", "achieved": 0, "unlocktime": 0, "name": "Legend", "description": "Earn 10,000,000g" }
+            ],
+            "success": true
+        }
+    })
+}
+
+/// The 400 body Steam sends for a game without stats.
+pub const NO_STATS: &str =
+    r#"{"playerstats":{"error":"Requested app has no stats","success":false}}"#;
+
+pub const APP_DETAILS: &str = "/api/appdetails";
+
+/// An `appdetails` body for Stardew Valley, trimmed to the fields we read.
+pub fn app_details() -> serde_json::Value {
+    serde_json::json!({
+        "413150": {
+            "success": true,
+            "data": {
+                "type": "game",
+                "name": "Stardew Valley",
+                "steam_appid": 413150,
+                "short_description": "You've inherited your grandfather's old farm plot in Stardew Valley.",
+                "developers": ["ConcernedApe"],
+                "publishers": ["ConcernedApe"],
+                "categories": [
+                    { "id": 2, "description": "Single-player" },
+                    { "id": 1, "description": "Multi-player" }
+                ],
+                "genres": [
+                    { "id": "23", "description": "Indie" },
+                    { "id": "28", "description": "Simulation" }
+                ],
+                "release_date": { "coming_soon": false, "date": "26 Feb, 2016" }
+            }
+        }
+    })
+}
@@ -8,12 +8,13 @@ pub struct SteamLibraryResponse {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SteamGamesResponse {
-    pub game_count: u32,
-    pub games: Vec<SteamGame>,
+    // Both are missing when the profile's game details are private
+    pub game_count: Option<u32>,
+    pub games: Option<Vec<SteamGame>>,
 }
 
 // Individual game from Steam API
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct SteamGame {
     pub appid: u64,
     pub name: String,
@@ -22,33 +23,411 @@ pub struct SteamGame {
     pub img_icon_url: String,
     pub rtime_last_played: u64, // Unix timestamp
     #[serde(default)]
+    pub playtime_windows_forever: u64,
+    #[serde(default)]
+    pub playtime_mac_forever: u64,
+    #[serde(default)]
     pub playtime_linux_forever: u64,
     #[serde(default)]
     pub playtime_deck_forever: u64,
+    // Filled in by `SteamClient::fetch_achievements`, never by GetOwnedGames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub achievements: Option<GameAchievements>,
+    // Filled in by `SteamClient::fetch_store_details`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store: Option<StoreDetails>,
+    // Filled in by `SteamClient::download_artwork`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artwork: Option<GameArtwork>,
+    // Filled in from recorded snapshots by `PlaytimeHistory::apply`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recent_playtime: Option<RecentPlaytime>,
+    // Filled in from local appmanifest files by `local_steam::merge_installed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed: Option<InstallInfo>,
 }
 
 impl SteamGame {
+    /// Non-Steam games added as shortcuts get app IDs with the top bit of
+    /// 32 set, so they never collide with store apps.
+    pub fn is_shortcut(&self) -> bool {
+        self.appid >= 0x8000_0000
+    }
+
+    pub fn playtime_hours(&self) -> f64 {
+        self.playtime_forever as f64 / 60.0
+    }
+
+    /// Minutes played on each platform. Steam counts Deck time as Linux
+    /// time too, so Linux here means desktop Linux only.
+    pub fn platform_playtime(&self) -> [(&'static str, u64); 4] {
+        [
+            ("Windows", self.playtime_windows_forever),
+            ("Mac", self.playtime_mac_forever),
+            (
+                "Linux",
+                self.playtime_linux_forever
+                    .saturating_sub(self.playtime_deck_forever),
+            ),
+            ("Steam Deck", self.playtime_deck_forever),
+        ]
+    }
+}
+
+// Player achievements API response structure
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerAchievementsResponse {
+    pub playerstats: PlayerStats,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerStats {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Achievement {
+    pub apiname: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub achieved: u8,
+    #[serde(default)]
+    pub unlocktime: u64, // Unix timestamp, 0 while locked
+}
+
+/// Everything a player has and hasn't unlocked in one game.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GameAchievements {
+    pub achievements: Vec<Achievement>,
+}
+
+impl GameAchievements {
+    pub fn total(&self) -> usize {
+        self.achievements.len()
+    }
+
+    pub fn unlocked(&self) -> usize {
+        self.achievements.iter().filter(|a| a.achieved != 0).count()
+    }
+
+    pub fn percent(&self) -> f64 {
+        if self.achievements.is_empty() {
+            0.0
+        } else {
+            self.unlocked() as f64 * 100.0 / self.total() as f64
+        }
+    }
+
+    /// Unlocked achievements, most recent first.
+    pub fn unlocked_by_date(&self) -> Vec<&Achievement> {
+        let mut unlocked: Vec<&Achievement> = self
+            .achievements
+            .iter()
+            .filter(|a| a.achieved != 0)
+            .collect();
+        unlocked.sort_by(|a, b| {
+            b.unlocktime
+                .cmp(&a.unlocktime)
+                .then_with(|| a.apiname.cmp(&b.apiname))
+        });
+        unlocked
+    }
+}
+
+// Store appdetails API response structure, keyed by appid
+pub type AppDetailsResponse = std::collections::HashMap<String, AppDetailsEntry>;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppDetailsEntry {
+    pub success: bool,
+    #[serde(default)]
+    pub data: Option<AppDetailsData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppDetailsData {
+    #[serde(default)]
+    pub short_description: String,
+    #[serde(default)]
+    pub developers: Vec<String>,
+    #[serde(default)]
+    pub publishers: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<Description>,
+    #[serde(default)]
+    pub categories: Vec<Description>,
+    #[serde(default)]
+    pub release_date: Option<ReleaseDate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Description {
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseDate {
+    #[serde(default)]
+    pub coming_soon: bool,
+    #[serde(default)]
+    pub date: String,
+}
+
+/// Where and how big an installed game is.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct InstallInfo {
+    pub size_on_disk: u64, // Bytes
+    pub last_updated: u64, // Unix timestamp
+    pub library: String,
+}
+
+impl InstallInfo {
+    /// Size as "12.3 GB" or "456 MB".
+    pub fn size_display(&self) -> String {
+        let gb = self.size_on_disk as f64 / 1_000_000_000.0;
+        if gb >= 1.0 {
+            format!("{:.1} GB", gb)
+        } else {
+            format!("{:.0} MB", self.size_on_disk as f64 / 1_000_000.0)
+        }
+    }
+}
+
+/// Minutes played in recent windows, from playtime snapshots.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RecentPlaytime {
+    pub week: u64,
+    pub month: u64,
+    pub year: u64,
+    pub series: Vec<PlaytimePoint>,
+}
+
+/// Total minutes played as of a date.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaytimePoint {
+    pub date: chrono::NaiveDate,
+    pub minutes: u64,
+}
+
+/// Site paths of a game's downloaded images, `None` where Steam has none.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GameArtwork {
+    pub icon: Option<String>,
+    pub header: Option<String>,
+    pub capsule: Option<String>,
+}
+
+impl GameArtwork {
+    /// Smallest wide image for lists, falling back to the icon.
+    pub fn thumbnail(&self) -> Option<&str> {
+        self.capsule
+            .as_deref()
+            .or(self.header.as_deref())
+            .or(self.icon.as_deref())
+    }
+}
+
+/// Store page metadata for one game.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StoreDetails {
+    pub short_description: String,
+    pub developers: Vec<String>,
+    pub publishers: Vec<String>,
+    pub genres: Vec<String>,
+    pub categories: Vec<String>,
+    pub release_date: String,
+    pub coming_soon: bool,
+}
+
+impl From<AppDetailsData> for StoreDetails {
+    fn from(data: AppDetailsData) -> Self {
+        let release = data.release_date.unwrap_or(ReleaseDate {
+            coming_soon: false,
+            date: String::new(),
+        });
+        Self {
+            short_description: data.short_description.trim().to_string(),
+            developers: data.developers,
+            publishers: data.publishers,
+            genres: data.genres.into_iter().map(|g| g.description).collect(),
+            categories: data.categories.into_iter().map(|c| c.description).collect(),
+            release_date: release.date,
+            coming_soon: release.coming_soon,
+        }
+    }
+}
+
+/// Unix timestamp as "YYYY-MM-DD".
+pub fn format_date(timestamp: u64) -> String {
+    use chrono::{DateTime, Utc};
+    match DateTime::<Utc>::from_timestamp(timestamp as i64, 0) {
+        Some(dt) => dt.format("%Y-%m-%d").to_string(),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Games as returned by Steam, before merging with other stores.
+pub type SteamLibrary = Vec<SteamGame>;
+
+/// A game owned on one store, with the playtime that store recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ownership {
+    pub store: String,    // Display name, e.g. "GOG"
+    pub id: String,       // The store's own ID for the game
+    pub playtime: u64,    // Minutes
+    pub last_played: u64, // Unix timestamp, 0 if never
+}
+
+/// Where a game stands in the player's backlog, from the games overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Playing,
+    Backlog,
+    Beaten,
+    Abandoned,
+}
+
+impl Status {
+    /// Every status, in the order the games index lists them.
+    pub const ALL: [Status; 4] = [
+        Status::Playing,
+        Status::Backlog,
+        Status::Beaten,
+        Status::Abandoned,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Status::Playing => "Playing",
+            Status::Backlog => "Backlog",
+            Status::Beaten => "Beaten",
+            Status::Abandoned => "Abandoned",
+        }
+    }
+}
+
+/// A game from any store. Copies owned on several stores are merged into one
+/// game, keeping each store's ownership and playtime.
+#[derive(Debug, Clone, Default)]
+pub struct Game {
+    pub name: String,
+    pub owned: Vec<Ownership>,
+    // The Steam record with everything the Steam enrichment steps filled in
+    pub steam: Option<SteamGame>,
+    // Set from the games overlay
+    pub status: Option<Status>,
+    pub rating: Option<u8>, // Out of 10
+    pub notes: String,
+}
+
+impl Game {
+    pub fn new(name: String, owned: Ownership) -> Self {
+        Self {
+            name,
+            owned: vec![owned],
+            ..Self::default()
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.name.is_empty()
     }
 
+    /// Identifies the game across the library: the store and ID it was
+    /// first seen under.
+    pub fn key(&self) -> String {
+        match self.owned.first() {
+            Some(owned) => format!("{}:{}", owned.store, owned.id),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Steam app ID, for games owned on Steam.
+    pub fn appid(&self) -> Option<u64> {
+        self.steam.as_ref().map(|steam| steam.appid)
+    }
+
+    /// Minutes played across every store.
+    pub fn playtime(&self) -> u64 {
+        self.owned.iter().map(|owned| owned.playtime).sum()
+    }
+
     pub fn playtime_hours(&self) -> f64 {
-        self.playtime_forever as f64 / 60.0
+        self.playtime() as f64 / 60.0
+    }
+
+    /// Most recent time played on any store, 0 if never.
+    pub fn last_played(&self) -> u64 {
+        self.owned
+            .iter()
+            .map(|owned| owned.last_played)
+            .max()
+            .unwrap_or(0)
     }
 
     pub fn last_played_date(&self) -> String {
-        if self.rtime_last_played == 0 {
-            "Never".to_string()
-        } else {
-            // Convert Unix timestamp to readable date
-            use chrono::{DateTime, Utc};
-            let datetime = DateTime::<Utc>::from_timestamp(self.rtime_last_played as i64, 0);
-            match datetime {
-                Some(dt) => dt.format("%Y-%m-%d").to_string(),
-                None => "Unknown".to_string(),
+        match self.last_played() {
+            0 => "Never".to_string(),
+            timestamp => format_date(timestamp),
+        }
+    }
+
+    /// Add ownership from another store. Ownership on a store the game is
+    /// already known on is combined rather than counted twice, since e.g.
+    /// GOG Galaxy reports the Steam playtime of games it imports from Steam.
+    pub fn add_ownership(&mut self, other: Ownership) {
+        match self
+            .owned
+            .iter_mut()
+            .find(|owned| owned.store == other.store && owned.id == other.id)
+        {
+            Some(owned) => {
+                owned.playtime = owned.playtime.max(other.playtime);
+                owned.last_played = owned.last_played.max(other.last_played);
             }
+            None => self.owned.push(other),
         }
     }
 }
 
-pub type GameLibrary = Vec<SteamGame>;
+impl From<SteamGame> for Game {
+    fn from(game: SteamGame) -> Self {
+        Self {
+            name: game.name.clone(),
+            owned: vec![Ownership {
+                store: STEAM.to_string(),
+                id: game.appid.to_string(),
+                playtime: game.playtime_forever,
+                last_played: game.rtime_last_played,
+            }],
+            steam: Some(game),
+            ..Self::default()
+        }
+    }
+}
+
+// Store names shared by importers, so ownership of the same store merges
+pub const STEAM: &str = "Steam";
+pub const GOG: &str = "GOG";
+pub const EPIC: &str = "Epic Games Store";
+pub const AMAZON: &str = "Amazon Games";
+
+/// Games from every store, one per title.
+pub type GameLibrary = Vec<Game>;
+
+/// Lowercase words of a title, ignoring punctuation and trademark signs, so
+/// "Clair Obscur: Expedition 33" and "CLAIR OBSCUR - Expedition 33" agree.
+pub fn title_words(title: &str) -> Vec<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.replace('\'', ""))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
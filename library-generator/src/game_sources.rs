@@ -0,0 +1,196 @@
+//! Game libraries from stores other than Steam, and merging them into one.
+
+use crate::game::{self, Game, GameLibrary, title_words};
+use std::collections::HashMap;
+
+mod gog_galaxy;
+mod heroic;
+mod lutris;
+
+pub use gog_galaxy::gog_galaxy_library;
+pub use heroic::heroic_library;
+pub use lutris::lutris_library;
+
+/// Display name of a store from the short key launchers use for it, e.g.
+/// GOG Galaxy's release key prefixes or Lutris's services.
+fn store_name(key: &str) -> String {
+    match key {
+        "steam" => game::STEAM,
+        "gog" => game::GOG,
+        "epic" | "egs" | "legendary" => game::EPIC,
+        "amazon" | "nile" => game::AMAZON,
+        "uplay" | "ubisoft" => "Ubisoft Connect",
+        "origin" | "ea_app" => "EA app",
+        "battlenet" => "Battle.net",
+        "xboxone" => "Xbox",
+        "humble" | "humblebundle" => "Humble Bundle",
+        "itch" | "itchio" => "itch.io",
+        "psn" => "PlayStation",
+        "rockstar" => "Rockstar Games Launcher",
+        key => key,
+    }
+    .to_string()
+}
+
+/// Merge games owned on several stores into one: copies with the same store
+/// and ID first, then copies with the same normalized title. Games keep the
+/// position and name of their first copy, so libraries are passed in order
+/// of preference.
+pub fn merge_libraries(libraries: Vec<Vec<Game>>) -> GameLibrary {
+    let mut merged: GameLibrary = Vec::new();
+    let mut by_title: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut by_ownership: HashMap<(String, String), usize> = HashMap::new();
+
+    for game in libraries.into_iter().flatten() {
+        let words = title_words(&game.name);
+
+        // The same store copy under another title, e.g. a launcher's copy
+        // of a Steam game named differently from the Steam API's
+        let same_copy = game.owned.iter().find_map(|owned| {
+            by_ownership
+                .get(&(owned.store.clone(), owned.id.clone()))
+                .copied()
+        });
+
+        // Two different games on the same store are never one game, e.g. a
+        // remake sharing its original's title. Untitled games only match by
+        // store copy.
+        let index = same_copy.or_else(|| {
+            by_title.get(&words).copied().filter(|&index| {
+                !words.is_empty()
+                    && !merged[index].owned.iter().any(|existing| {
+                        game.owned
+                            .iter()
+                            .any(|owned| owned.store == existing.store && owned.id != existing.id)
+                    })
+            })
+        });
+        for owned in &game.owned {
+            by_ownership.insert(
+                (owned.store.clone(), owned.id.clone()),
+                index.unwrap_or(merged.len()),
+            );
+        }
+        match index {
+            Some(index) => {
+                let existing = &mut merged[index];
+                for owned in game.owned {
+                    existing.add_ownership(owned);
+                }
+                if existing.steam.is_none() {
+                    existing.steam = game.steam;
+                }
+            }
+            None => {
+                if !words.is_empty() {
+                    by_title.entry(words).or_insert(merged.len());
+                }
+                merged.push(game);
+            }
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Ownership, SteamGame};
+
+    fn owned(store: &str, id: &str, playtime: u64) -> Ownership {
+        Ownership {
+            store: store.to_string(),
+            id: id.to_string(),
+            playtime,
+            last_played: 0,
+        }
+    }
+
+    #[test]
+    fn merges_copies_by_normalized_title() {
+        let steam = vec![
+            Game::from(SteamGame {
+                appid: 1091500,
+                name: "Cyberpunk 2077".to_string(),
+                playtime_forever: 600,
+                ..SteamGame::default()
+            }),
+            Game::from(SteamGame {
+                appid: 2280,
+                name: "DOOM".to_string(),
+                ..SteamGame::default()
+            }),
+        ];
+        let galaxy = vec![
+            Game::new(
+                "Cyberpunk 2077™".to_string(),
+                owned("GOG", "1423049311", 120),
+            ),
+            // Galaxy's copy of the Steam playtime isn't counted twice
+            Game::new("Cyberpunk 2077".to_string(), owned("Steam", "1091500", 590)),
+            Game::new(
+                "The Witcher 3".to_string(),
+                owned("GOG", "1207664643", 3000),
+            ),
+        ];
+        let lutris = vec![
+            Game::new(
+                "the witcher 3".to_string(),
+                owned("Lutris", "the-witcher-3", 60),
+            ),
+            // A different Steam game that happens to share a title
+            Game::new("Doom".to_string(), owned("Steam", "379720", 30)),
+        ];
+
+        let library = merge_libraries(vec![steam, galaxy, lutris]);
+        let summary: Vec<(&str, Vec<&str>, u64)> = library
+            .iter()
+            .map(|game| {
+                (
+                    game.name.as_str(),
+                    game.owned.iter().map(|o| o.store.as_str()).collect(),
+                    game.playtime(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("Cyberpunk 2077", vec!["Steam", "GOG"], 720),
+                ("DOOM", vec!["Steam"], 0),
+                ("The Witcher 3", vec!["GOG", "Lutris"], 3060),
+                ("Doom", vec!["Steam"], 30),
+            ]
+        );
+        assert_eq!(library[0].appid(), Some(1091500));
+        assert_eq!(library[0].key(), "Steam:1091500");
+    }
+
+    #[test]
+    fn merges_same_store_copy_under_another_title() {
+        let steam = vec![Game::from(SteamGame {
+            appid: 292030,
+            name: "The Witcher 3: Wild Hunt".to_string(),
+            playtime_forever: 3000,
+            ..SteamGame::default()
+        })];
+        let galaxy = vec![Game::new(
+            "The Witcher® 3: Wild Hunt - Game of the Year Edition".to_string(),
+            owned("Steam", "292030", 2990),
+        )];
+        let lutris = vec![Game::new(
+            "Witcher 3 GOTY".to_string(),
+            owned("Steam", "292030", 0),
+        )];
+
+        let library = merge_libraries(vec![steam, galaxy, lutris]);
+
+        assert_eq!(library.len(), 1);
+        assert_eq!(library[0].name, "The Witcher 3: Wild Hunt");
+        assert_eq!(library[0].owned.len(), 1);
+        assert_eq!(library[0].playtime(), 3000);
+        assert!(library[0].steam.is_some());
+    }
+}
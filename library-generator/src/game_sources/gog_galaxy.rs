@@ -0,0 +1,157 @@
+use super::store_name;
+use crate::game::{Game, Ownership};
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+// Every release in the Galaxy library with its title, minutes played and
+// last played date. Titles are JSON game pieces like {"title": "..."}.
+const RELEASES_QUERY: &str = "
+    SELECT r.releaseKey,
+        (SELECT p.value FROM GamePieces p
+            JOIN GamePieceTypes t ON t.id = p.gamePieceTypeId
+            WHERE p.releaseKey = r.releaseKey AND t.type IN ('title', 'originalTitle')
+            ORDER BY t.type DESC LIMIT 1),
+        (SELECT SUM(g.minutesInGame) FROM GameTimes g WHERE g.releaseKey = r.releaseKey),
+        (SELECT MAX(l.lastPlayedDate) FROM LastPlayedDates l
+            WHERE l.gameReleaseKey = r.releaseKey)
+    FROM LibraryReleases r
+    GROUP BY r.releaseKey
+    ORDER BY r.releaseKey";
+
+#[derive(serde::Deserialize)]
+struct TitlePiece {
+    title: Option<String>,
+}
+
+/// Games from GOG Galaxy 2.0's `galaxy-2.0.db`. Galaxy also lists games it
+/// imports from other launchers, so each keeps the store its release key
+/// names ("gog_1207658924", "steam_413150", ...).
+pub fn gog_galaxy_library(path: &Path) -> Result<Vec<Game>> {
+    let conn =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to open GOG Galaxy database '{}': {}",
+                path.display(),
+                e
+            )
+        })?;
+
+    let mut stmt = conn.prepare(RELEASES_QUERY)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut games = Vec::new();
+    for row in rows {
+        let (release_key, title, minutes, last_played) = row?;
+        let Some((store, id)) = release_key.split_once('_') else {
+            continue;
+        };
+        let Some(title) = title
+            .and_then(|piece| serde_json::from_str::<TitlePiece>(&piece).ok())
+            .and_then(|piece| piece.title)
+        else {
+            eprintln!(
+                "Skipping GOG Galaxy release {} without a title",
+                release_key
+            );
+            continue;
+        };
+
+        games.push(Game::new(
+            title,
+            Ownership {
+                store: match store {
+                    // Games added to Galaxy by hand
+                    "generic" => "GOG Galaxy".to_string(),
+                    store => store_name(store),
+                },
+                id: id.to_string(),
+                playtime: minutes.unwrap_or(0).max(0) as u64,
+                last_played: last_played.as_deref().map_or(0, timestamp),
+            },
+        ));
+    }
+
+    println!("Found {} games in GOG Galaxy", games.len());
+    Ok(games)
+}
+
+// Galaxy's "YYYY-MM-DD HH:MM:SS" dates, in UTC
+fn timestamp(date: &str) -> u64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+        .map(|date| date.and_utc().timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_releases_with_playtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("galaxy-2.0.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE LibraryReleases (id INTEGER PRIMARY KEY, releaseKey TEXT, userId INTEGER);
+            CREATE TABLE GamePieceTypes (id INTEGER PRIMARY KEY, type TEXT);
+            CREATE TABLE GamePieces (id INTEGER PRIMARY KEY, releaseKey TEXT,
+                gamePieceTypeId INTEGER, userId INTEGER, value TEXT);
+            CREATE TABLE GameTimes (userId INTEGER, releaseKey TEXT, minutesInGame INTEGER);
+            CREATE TABLE LastPlayedDates (userId INTEGER, gameReleaseKey TEXT, lastPlayedDate TEXT);
+
+            INSERT INTO GamePieceTypes VALUES (1, 'originalTitle'), (2, 'title');
+            INSERT INTO LibraryReleases (releaseKey, userId) VALUES
+                ('gog_1207664643', 1), ('steam_413150', 1), ('epic_fn', 1), ('gog_404', 1);
+            INSERT INTO GamePieces (releaseKey, gamePieceTypeId, userId, value) VALUES
+                ('gog_1207664643', 1, 1, '{"title": "The Witcher 3"}'),
+                ('gog_1207664643', 2, 1, '{"title": "The Witcher 3: Wild Hunt"}'),
+                ('steam_413150', 1, 1, '{"title": "Stardew Valley"}'),
+                ('epic_fn', 2, 1, '{"title": "Fortnite"}');
+            INSERT INTO GameTimes VALUES (1, 'gog_1207664643', 3000), (1, 'steam_413150', 90);
+            INSERT INTO LastPlayedDates VALUES (1, 'gog_1207664643', '2024-01-02 03:04:05');
+            "#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let games = gog_galaxy_library(&path).unwrap();
+        let summary: Vec<(&str, &str, &str, u64, u64)> = games
+            .iter()
+            .map(|game| {
+                let owned = &game.owned[0];
+                (
+                    game.name.as_str(),
+                    owned.store.as_str(),
+                    owned.id.as_str(),
+                    owned.playtime,
+                    owned.last_played,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("Fortnite", "Epic Games Store", "fn", 0, 0),
+                (
+                    "The Witcher 3: Wild Hunt",
+                    "GOG",
+                    "1207664643",
+                    3000,
+                    1704164645
+                ),
+                ("Stardew Valley", "Steam", "413150", 90, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::game::{self, Game, Ownership};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Library caches under Heroic's config directory, one per store it wraps
+const LIBRARIES: &[(&str, &str)] = &[
+    ("store_cache/gog_library.json", game::GOG),
+    ("store_cache/legendary_library.json", game::EPIC),
+    ("store_cache/nile_library.json", game::AMAZON),
+];
+
+// Playtime Heroic tracks itself, keyed by app name
+const PLAYTIME_FILE: &str = "store/timestamp.json";
+
+// The GOG cache lists "games", the others a "library"
+#[derive(Deserialize)]
+struct HeroicLibrary {
+    #[serde(default, alias = "library")]
+    games: Vec<HeroicGame>,
+}
+
+#[derive(Deserialize)]
+struct HeroicGame {
+    app_name: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    is_dlc: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeroicPlaytime {
+    #[serde(default)]
+    last_played: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    total_played: u64, // Minutes
+}
+
+/// Games from the Heroic Games Launcher's config directory (e.g.
+/// ~/.config/heroic), for each of the GOG, Epic and Amazon libraries it
+/// has cached. Stores the user never logged in to are simply missing.
+pub fn heroic_library(config_dir: &Path) -> Result<Vec<Game>> {
+    let playtime_path = config_dir.join(PLAYTIME_FILE);
+    let playtime: HashMap<String, HeroicPlaytime> = if playtime_path.exists() {
+        read_json(&playtime_path)?
+    } else {
+        HashMap::new()
+    };
+
+    let mut games = Vec::new();
+    for (file, store) in LIBRARIES {
+        let path = config_dir.join(file);
+        if !path.exists() {
+            continue;
+        }
+
+        let library: HeroicLibrary = read_json(&path)?;
+        for entry in library.games {
+            if entry.is_dlc || entry.title.is_empty() {
+                continue;
+            }
+            let played = playtime.get(&entry.app_name);
+            games.push(Game::new(
+                entry.title,
+                Ownership {
+                    store: store.to_string(),
+                    id: entry.app_name,
+                    playtime: played.map(|p| p.total_played).unwrap_or(0),
+                    last_played: played
+                        .and_then(|p| p.last_played)
+                        .map(|date| date.timestamp().max(0) as u64)
+                        .unwrap_or(0),
+                },
+            ));
+        }
+    }
+
+    println!("Found {} games in Heroic", games.len());
+    Ok(games)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Failed to parse '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cached_libraries_and_playtime() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("store_cache")).unwrap();
+        fs::create_dir_all(dir.path().join("store")).unwrap();
+        fs::write(
+            dir.path().join("store_cache/gog_library.json"),
+            r#"{"games": [
+                {"app_name": "1207664643", "title": "The Witcher 3", "is_installed": true},
+                {"app_name": "1", "title": "The Witcher 3 - Hearts of Stone", "is_dlc": true}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("store_cache/legendary_library.json"),
+            r#"{"library": [{"app_name": "Fortnite", "title": "Fortnite"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("store/timestamp.json"),
+            r#"{"1207664643": {
+                "firstPlayed": "2023-12-01T10:00:00.000Z",
+                "lastPlayed": "2024-01-02T03:04:05.000Z",
+                "totalPlayed": 125
+            }}"#,
+        )
+        .unwrap();
+
+        let games = heroic_library(dir.path()).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].name, "The Witcher 3");
+        assert_eq!(
+            games[0].owned,
+            [Ownership {
+                store: "GOG".to_string(),
+                id: "1207664643".to_string(),
+                playtime: 125,
+                last_played: 1704164645,
+            }]
+        );
+        assert_eq!(games[1].owned[0].store, "Epic Games Store");
+        assert_eq!(games[1].playtime(), 0);
+    }
+}
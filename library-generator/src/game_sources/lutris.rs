@@ -0,0 +1,110 @@
+use super::store_name;
+use crate::game::{Game, Ownership};
+use anyhow::Result;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+// Store name for games added to Lutris directly rather than from a service
+const LUTRIS: &str = "Lutris";
+
+/// Games from Lutris's `pga.db`. Games imported from a service (Steam, GOG,
+/// Epic, ...) count as owned there, so they merge with that store's copy;
+/// the rest are owned on Lutris itself.
+pub fn lutris_library(path: &Path) -> Result<Vec<Game>> {
+    let conn =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| {
+            anyhow::anyhow!("Failed to open Lutris database '{}': {}", path.display(), e)
+        })?;
+
+    let mut stmt = conn.prepare(
+        "SELECT name, slug, service, service_id, playtime, lastplayed FROM games ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<f64>>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+        ))
+    })?;
+
+    let mut games = Vec::new();
+    for row in rows {
+        let (name, slug, service, service_id, hours, last_played) = row?;
+        let Some(name) = name.filter(|name| !name.is_empty()) else {
+            continue;
+        };
+
+        let (store, id) = match (service, service_id) {
+            (Some(service), Some(id)) if !service.is_empty() && !id.is_empty() => {
+                (store_name(&service), id)
+            }
+            _ => (LUTRIS.to_string(), slug.unwrap_or_else(|| name.clone())),
+        };
+
+        games.push(Game::new(
+            name,
+            Ownership {
+                store,
+                id,
+                // Lutris keeps playtime in hours
+                playtime: (hours.unwrap_or(0.0) * 60.0).round().max(0.0) as u64,
+                last_played: last_played.unwrap_or(0).max(0) as u64,
+            },
+        ));
+    }
+
+    println!("Found {} games in Lutris", games.len());
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_games_with_their_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pga.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE games (id INTEGER PRIMARY KEY, name TEXT, slug TEXT, runner TEXT,
+                service TEXT, service_id TEXT, playtime REAL, lastplayed INTEGER);
+            INSERT INTO games (name, slug, runner, service, service_id, playtime, lastplayed)
+            VALUES
+                ('Celeste', 'celeste', 'linux', 'gog', '1307279151', 2.5, 1700000000),
+                ('osu!', 'osu', 'wine', NULL, NULL, 0.75, NULL),
+                ('Hades', 'hades', 'steam', 'steam', '1145360', NULL, NULL);
+            ",
+        )
+        .unwrap();
+        drop(conn);
+
+        let games = lutris_library(&path).unwrap();
+        let summary: Vec<(&str, &str, &str, u64, u64)> = games
+            .iter()
+            .map(|game| {
+                let owned = &game.owned[0];
+                (
+                    game.name.as_str(),
+                    owned.store.as_str(),
+                    owned.id.as_str(),
+                    owned.playtime,
+                    owned.last_played,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("Celeste", "GOG", "1307279151", 150, 1700000000),
+                ("osu!", "Lutris", "osu", 45, 0),
+                ("Hades", "Steam", "1145360", 0, 0),
+            ]
+        );
+    }
+}
@@ -1,12 +1,28 @@
-use crate::game::{GameLibrary, SteamGame};
+use crate::game::{
+    Game, GameAchievements, GameLibrary, InstallInfo, Status, SteamGame, format_date,
+};
 use crate::library::{Album, Artist, Library};
+use crate::manifest::{GAMES, MUSIC, Manifest};
+use crate::slugs::SlugRegistry;
+use crate::soundtracks::{SoundtrackLink, Soundtracks};
+use crate::taxonomy::{credits, term_slug};
 use crate::track::Track;
 use anyhow::Result;
 use serde::Serialize;
-use slug::slugify;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tera::Tera;
 
+mod game_lists;
+use game_lists::BacklogSummary;
+pub use game_lists::BacklogThresholds;
+mod game_terms;
+mod terms;
+#[cfg(test)]
+pub(crate) mod testing;
+mod years;
+
 // Context struct for track page template
 #[derive(Serialize)]
 struct TrackContext {
@@ -14,9 +30,12 @@ struct TrackContext {
     template: String,
     track: String,
     artist: String,
+    artist_slug: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     album: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    album_slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     year: Option<String>,
     format: String,
     bitrate: String,
@@ -24,6 +43,14 @@ struct TrackContext {
     #[serde(skip_serializing_if = "Option::is_none")]
     genre: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    genre_slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<TermRef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    composers: Vec<TermRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country: Option<TermRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     comments: Option<String>,
     search_content: String,
     url: String,
@@ -43,6 +70,7 @@ struct ArtistContext {
 #[derive(Serialize)]
 struct AlbumSummary {
     title: String,
+    slug: String,
     year: String,
     tracks: usize,
 }
@@ -50,6 +78,7 @@ struct AlbumSummary {
 #[derive(Serialize)]
 struct TrackSummary {
     title: String,
+    slug: String,
     length: String,
     year: String,
 }
@@ -61,9 +90,20 @@ struct AlbumContext {
     template: String,
     album: String,
     artist: String,
+    artist_slug: String,
     #[serde(skip_serializing_if = "String::is_empty")]
     year: String,
     genre: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    genre_slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<TermRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country: Option<TermRef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    composers: Vec<TermRef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    games: Vec<SoundtrackLink>,
     tracktotal: u32,
     tracks: Vec<TrackInAlbum>,
 }
@@ -71,6 +111,7 @@ struct AlbumContext {
 #[derive(Serialize)]
 struct TrackInAlbum {
     title: String,
+    slug: String,
     length: String,
 }
 
@@ -92,19 +133,140 @@ struct ArtistLink {
     slug: String,
 }
 
+// Link to a term page: a label, composer, country, developer, ...
+#[derive(Serialize)]
+struct TermRef {
+    name: String,
+    slug: String,
+}
+
+impl TermRef {
+    fn new(value: &str) -> Option<Self> {
+        let name = value.trim();
+        if name.is_empty() {
+            None
+        } else {
+            Some(Self {
+                name: name.to_string(),
+                slug: term_slug(name),
+            })
+        }
+    }
+}
+
 // Context struct for game page template
 #[derive(Serialize)]
 struct GameContext {
     title: String,
     template: String,
     game: String,
-    appid: u64,
+    appid: Option<u64>,
+    shortcut: bool,
     playtime_hours: String,
     last_played: String,
+    owned: Vec<OwnershipContext>,
+    status: Option<String>,
+    rating: Option<u8>,
+    notes: String,
+    platforms: Vec<PlatformPlaytime>,
+    installed: Option<InstalledContext>,
+    recent: Option<RecentContext>,
+    playtime_history: Vec<PlaytimePointContext>,
+    achievements: Option<AchievementsContext>,
+    store: Option<StoreContext>,
+    soundtracks: Vec<SoundtrackLink>,
+    icon: Option<String>,
+    header_image: Option<String>,
+    capsule_image: Option<String>,
     search_content: String,
     url: String,
 }
 
+// One store a game is owned on, with that store's playtime
+#[derive(Serialize)]
+struct OwnershipContext {
+    store: String,
+    hours: String,
+    last_played: String,
+}
+
+#[derive(Serialize)]
+struct PlatformPlaytime {
+    platform: String,
+    hours: String,
+}
+
+impl PlatformPlaytime {
+    // Platforms with any playtime, in a fixed order
+    fn list(playtime: [(&'static str, u64); 4]) -> Vec<Self> {
+        playtime
+            .into_iter()
+            .filter(|(_, minutes)| *minutes > 0)
+            .map(|(platform, minutes)| Self {
+                platform: platform.to_string(),
+                hours: format!("{:.1}", minutes as f64 / 60.0),
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+struct InstalledContext {
+    size: String,
+    last_updated: String,
+    library: String,
+}
+
+// Hours played in the last week, month and year
+#[derive(Serialize)]
+struct RecentContext {
+    week: String,
+    month: String,
+    year: String,
+}
+
+impl RecentContext {
+    fn new(week: u64, month: u64, year: u64) -> Self {
+        let hours = |minutes: u64| format!("{:.1}", minutes as f64 / 60.0);
+        Self {
+            week: hours(week),
+            month: hours(month),
+            year: hours(year),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PlaytimePointContext {
+    date: String,
+    hours: String,
+}
+
+#[derive(Serialize)]
+struct AchievementsContext {
+    unlocked: usize,
+    total: usize,
+    percent: String,
+    unlocks: Vec<AchievementUnlock>,
+}
+
+#[derive(Serialize)]
+struct StoreContext {
+    short_description: String,
+    developers: Vec<TermRef>,
+    publishers: Vec<String>,
+    genres: Vec<TermRef>,
+    categories: Vec<String>,
+    release_date: String,
+}
+
+#[derive(Serialize)]
+struct AchievementUnlock {
+    name: String,
+    description: String,
+    date: String,
+}
+
 // Context structs for games index page template
 #[derive(Serialize)]
 struct GamesIndexContext {
@@ -113,7 +275,18 @@ struct GamesIndexContext {
     template: String,
     game_count: usize,
     total_hours: String,
+    platform_totals: Vec<PlatformPlaytime>,
+    deck_game_count: usize,
+    installed_count: usize,
+    installed_size: String,
+    recent: Option<RecentContext>,
+    recently_played_count: usize,
+    backlog: BacklogSummary,
+    store_counts: Vec<StoreCount>,
+    status_sections: Vec<StatusSection>,
     games: Vec<GameLink>,
+    completionist: Vec<GameLink>,
+    has_store_details: bool,
 }
 
 #[derive(Serialize)]
@@ -121,11 +294,45 @@ struct GameLink {
     name: String,
     slug: String,
     playtime_hours: String,
+    achievements: Option<String>,
+    thumbnail: Option<String>,
+    installed: bool,
+    stores: Vec<String>,
+    rating: Option<u8>,
+}
+
+// Games with one backlog status, for the games index
+#[derive(Serialize)]
+struct StatusSection {
+    status: String,
+    games: Vec<GameLink>,
+}
+
+#[derive(Serialize)]
+struct StoreCount {
+    store: String,
+    game_count: usize,
+    hours: String,
+}
+
+// Counts of what a run did to the output directory
+#[derive(Debug, Default)]
+struct WriteStats {
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    deleted: usize,
 }
 
 pub struct Generator {
     output_dir: String,
     tera: Tera,
+    manifest_path: PathBuf,
+    previous: Manifest,
+    manifest: Mutex<Manifest>,
+    stats: Mutex<WriteStats>,
+    soundtracks: Soundtracks,
+    backlog: BacklogThresholds,
 }
 
 // Custom filter for TOML string escaping
@@ -145,8 +352,43 @@ fn escape_toml_filter(
     }
 }
 
+// Minutes played per platform across the whole library, as Steam reports it
+fn platform_totals(library: &GameLibrary) -> [(&'static str, u64); 4] {
+    let mut totals = SteamGame::default().platform_playtime();
+    for game in library.iter().filter_map(|game| game.steam.as_ref()) {
+        for (total, (_, minutes)) in totals.iter_mut().zip(game.platform_playtime()) {
+            total.1 += minutes;
+        }
+    }
+    totals
+}
+
+// Games owned and hours played per store, most games first
+fn store_counts(library: &GameLibrary) -> Vec<StoreCount> {
+    let mut counts: Vec<(&str, usize, u64)> = Vec::new();
+    for owned in library.iter().flat_map(|game| &game.owned) {
+        match counts.iter_mut().find(|(store, ..)| *store == owned.store) {
+            Some((_, games, minutes)) => {
+                *games += 1;
+                *minutes += owned.playtime;
+            }
+            None => counts.push((&owned.store, 1, owned.playtime)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    counts
+        .into_iter()
+        .map(|(store, game_count, minutes)| StoreCount {
+            store: store.to_string(),
+            game_count,
+            hours: format!("{:.1}", minutes as f64 / 60.0),
+        })
+        .collect()
+}
+
 impl Generator {
-    pub fn new(output_dir: String) -> Result<Self> {
+    pub fn new(output_dir: String, manifest_path: PathBuf) -> Result<Self> {
         // Initialize Tera with templates
         let mut tera = Tera::new("content-templates/**/*.tera")
             .map_err(|e| anyhow::anyhow!("Failed to load templates: {}", e))?;
@@ -162,6 +404,13 @@ impl Generator {
             "index.md.tera",
             "game.md.tera",
             "games_index.md.tera",
+            "term.md.tera",
+            "terms_index.md.tera",
+            "year.md.tera",
+            "years_index.md.tera",
+            "game_term.md.tera",
+            "game_terms_index.md.tera",
+            "game_list.md.tera",
         ];
         for template in required {
             if !tera.get_template_names().any(|n| n == template) {
@@ -172,7 +421,132 @@ impl Generator {
             }
         }
 
-        Ok(Self { output_dir, tera })
+        let previous = Manifest::load(&manifest_path)?;
+
+        Ok(Self {
+            output_dir,
+            tera,
+            manifest_path,
+            previous,
+            manifest: Mutex::new(Manifest::default()),
+            stats: Mutex::new(WriteStats::default()),
+            soundtracks: Soundtracks::default(),
+            backlog: BacklogThresholds::default(),
+        })
+    }
+
+    /// Cross-link album and game pages through these soundtrack matches.
+    pub fn with_soundtracks(mut self, soundtracks: Soundtracks) -> Self {
+        self.soundtracks = soundtracks;
+        self
+    }
+
+    /// Use these cutoffs for the barely played and untouched game lists.
+    pub fn with_backlog_thresholds(mut self, backlog: BacklogThresholds) -> Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Write a generated file and record it in this run's manifest.
+    ///
+    /// Files whose rendered content matches what's already on disk are left
+    /// untouched, so unchanged pages keep their mtime and don't show up in
+    /// the site's git history.
+    fn write_page(&self, scope: &str, relative: String, content: String) -> Result<()> {
+        let path = format!("{}/{}", self.output_dir, relative);
+
+        let changed = match fs::read(&path) {
+            Ok(existing) if existing == content.as_bytes() => {
+                self.stats.lock().unwrap().unchanged += 1;
+                false
+            }
+            Ok(_) => {
+                self.stats.lock().unwrap().updated += 1;
+                true
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.stats.lock().unwrap().created += 1;
+                true
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to read file '{}': {}", path, e)),
+        };
+
+        if changed {
+            fs::write(&path, content)
+                .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        }
+
+        self.manifest.lock().unwrap().record(scope, relative);
+        Ok(())
+    }
+
+    /// Write a boilerplate section index unless it has been replaced by a
+    /// hand-written one, i.e. it exists but we never generated it. An index
+    /// identical to the boilerplate is adopted instead, since that's one an
+    /// earlier run wrote before it kept a manifest.
+    fn write_section_index(&self, relative: &str, content: &str) -> Result<()> {
+        let path = format!("{}/{}", self.output_dir, relative);
+        if Path::new(&path).exists() && !self.previous.contains(relative) {
+            let existing = fs::read(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read file '{}': {}", path, e))?;
+            if existing != content.as_bytes() {
+                return Ok(());
+            }
+        }
+
+        self.write_page(MUSIC, relative.to_string(), content.to_string())
+    }
+
+    /// Delete files generated by an earlier run that this run no longer
+    /// produced, then save the manifest.
+    ///
+    /// Only scopes generated in this run are considered. Stale files that are
+    /// kept (without `prune`, or with `dry_run`) stay in the manifest so a
+    /// later run can still prune them.
+    pub async fn finish(&self, prune: bool, dry_run: bool) -> Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+
+        for relative in manifest.stale_since(&self.previous) {
+            let path = Path::new(&self.output_dir).join(&relative);
+            if !path.exists() {
+                continue;
+            }
+
+            if prune && !dry_run {
+                fs::remove_file(&path).map_err(|e| {
+                    anyhow::anyhow!("Failed to remove file '{}': {}", path.display(), e)
+                })?;
+                println!("Pruned {}", path.display());
+                self.stats.lock().unwrap().deleted += 1;
+            } else {
+                if dry_run {
+                    println!("Would prune {}", path.display());
+                }
+                let scope = self.previous.scope_of(&relative).unwrap_or(MUSIC);
+                manifest.record(scope, relative);
+            }
+        }
+
+        // Keep entries for scopes that weren't generated this run
+        for (scope, files) in &self.previous.files {
+            if !manifest.files.contains_key(scope) {
+                manifest.files.insert(scope.clone(), files.clone());
+            }
+        }
+
+        // Only a changed file set is a new generation, so runs that produced
+        // the same files leave the manifest untouched too
+        if manifest.files != self.previous.files || !self.manifest_path.exists() {
+            manifest.generated_at = chrono::Utc::now().to_rfc3339();
+            manifest.save(&self.manifest_path)?;
+        }
+
+        let stats = self.stats.lock().unwrap();
+        println!(
+            "{} created, {} updated, {} unchanged, {} deleted",
+            stats.created, stats.updated, stats.unchanged, stats.deleted
+        );
+        Ok(())
     }
 
     pub async fn generate(&self, library: &Library) -> Result<()> {
@@ -182,8 +556,11 @@ impl Generator {
         fs::create_dir_all(format!("{}/albums", self.output_dir))?;
         fs::create_dir_all(format!("{}/tracks", self.output_dir))?;
 
+        // Resolve page slugs up front so colliding titles get distinct pages
+        let slugs = SlugRegistry::from_library(library);
+
         // Generate index page
-        self.generate_index(library).await?;
+        self.generate_index(library, &slugs).await?;
 
         // Generate section indexes
         self.generate_artists_section_index().await?;
@@ -192,25 +569,43 @@ impl Generator {
 
         // Generate artist pages
         for (artist_name, artist) in library {
-            self.generate_artist_page(artist_name, artist).await?;
+            self.generate_artist_page(artist_name, artist, &slugs)
+                .await?;
         }
 
         // Generate album pages
         for artist in library.values() {
             for album in &artist.albums {
-                self.generate_album_page(album).await?;
+                self.generate_album_page(artist, album, &slugs).await?;
             }
         }
 
+        // Generate browsable sections
+        self.generate_terms(&terms::GENRES, library, &slugs, |track| vec![&track.genre])
+            .await?;
+        self.generate_terms(&terms::LABELS, library, &slugs, |track| vec![&track.label])
+            .await?;
+        self.generate_terms(&terms::COMPOSERS, library, &slugs, |track| {
+            credits(&track.composer)
+        })
+        .await?;
+        self.generate_terms(&terms::COUNTRIES, library, &slugs, |track| {
+            vec![&track.country]
+        })
+        .await?;
+        self.generate_years(library, &slugs).await?;
+
         // Generate individual track pages
         for artist in library.values() {
             for album in &artist.albums {
                 for track in &album.tracks {
-                    self.generate_track_page(track).await?;
+                    self.generate_track_page(artist, Some(album), track, &slugs)
+                        .await?;
                 }
             }
             for track in &artist.tracks {
-                self.generate_track_page(track).await?;
+                self.generate_track_page(artist, None, track, &slugs)
+                    .await?;
             }
         }
 
@@ -218,7 +613,7 @@ impl Generator {
         Ok(())
     }
 
-    async fn generate_index(&self, library: &Library) -> Result<()> {
+    async fn generate_index(&self, library: &Library, slugs: &SlugRegistry) -> Result<()> {
         // Calculate statistics
         let artist_count = library.len();
         let album_count: usize = library.values().map(|a| a.albums.len()).sum();
@@ -227,16 +622,16 @@ impl Generator {
             .map(|a| a.albums.iter().map(|al| al.tracks.len()).sum::<usize>() + a.tracks.len())
             .sum();
 
-        // Build sorted artist list
-        let mut artist_names: Vec<&String> = library.keys().collect();
-        artist_names.sort();
-        let artists: Vec<ArtistLink> = artist_names
-            .iter()
-            .map(|&name| ArtistLink {
-                name: name.clone(),
-                slug: slugify(name),
+        // Build artist list (the library is already sorted by name)
+        let artists: Vec<ArtistLink> = library
+            .keys()
+            .map(|name| {
+                Ok(ArtistLink {
+                    name: name.clone(),
+                    slug: slugs.artist(name)?.to_string(),
+                })
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         // Create context
         let context = IndexContext {
@@ -256,37 +651,46 @@ impl Generator {
             .map_err(|e| anyhow::anyhow!("Failed to render index: {}", e))?;
 
         // Write file
-        let path = format!("{}/_index.md", self.output_dir);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(MUSIC, "_index.md".to_string(), content)?;
 
         Ok(())
     }
 
-    async fn generate_artist_page(&self, artist_name: &str, artist: &Artist) -> Result<()> {
-        let slug = slugify(artist_name);
+    async fn generate_artist_page(
+        &self,
+        artist_name: &str,
+        artist: &Artist,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let slug = slugs.artist(artist_name)?;
 
         // Build album summaries
         let albums: Vec<AlbumSummary> = artist
             .albums
             .iter()
-            .map(|album| AlbumSummary {
-                title: album.title.clone(),
-                year: album.year.clone(),
-                tracks: album.track_count(),
+            .map(|album| {
+                Ok(AlbumSummary {
+                    title: album.title.clone(),
+                    slug: slugs.album(album)?.to_string(),
+                    year: album.year.map(|y| y.to_string()).unwrap_or_default(),
+                    tracks: album.track_count(),
+                })
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         // Build standalone track summaries
         let tracks: Vec<TrackSummary> = artist
             .tracks
             .iter()
-            .map(|track| TrackSummary {
-                title: track.title.clone(),
-                length: track.length.clone(),
-                year: track.year.clone(),
+            .map(|track| {
+                Ok(TrackSummary {
+                    title: track.title.clone(),
+                    slug: slugs.track(track)?.to_string(),
+                    length: track.length_display(),
+                    year: track.year.map(|y| y.to_string()).unwrap_or_default(),
+                })
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         // Create context
         let context = ArtistContext {
@@ -304,25 +708,44 @@ impl Generator {
             .map_err(|e| anyhow::anyhow!("Failed to render artist '{}': {}", artist_name, e))?;
 
         // Write file
-        let path = format!("{}/artists/{}.md", self.output_dir, slug);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(MUSIC, format!("artists/{}.md", slug), content)?;
 
         Ok(())
     }
 
-    async fn generate_album_page(&self, album: &Album) -> Result<()> {
-        let slug = slugify(&album.title);
+    async fn generate_album_page(
+        &self,
+        artist: &Artist,
+        album: &Album,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let slug = slugs.album(album)?;
 
         // Build track list
         let tracks: Vec<TrackInAlbum> = album
             .tracks
             .iter()
-            .map(|track| TrackInAlbum {
-                title: track.title.clone(),
-                length: track.length.clone(),
+            .map(|track| {
+                Ok(TrackInAlbum {
+                    title: track.title.clone(),
+                    slug: slugs.track(track)?.to_string(),
+                    length: track.length_display(),
+                })
             })
-            .collect();
+            .collect::<Result<_>>()?;
+
+        // Distinct composers across the album, in track order
+        let mut composers: Vec<TermRef> = Vec::new();
+        for composer in album
+            .tracks
+            .iter()
+            .flat_map(|t| credits(&t.composer))
+            .filter_map(TermRef::new)
+        {
+            if !composers.iter().any(|c| c.slug == composer.slug) {
+                composers.push(composer);
+            }
+        }
 
         // Create context
         let context = AlbumContext {
@@ -330,8 +753,24 @@ impl Generator {
             template: "album.html".to_string(),
             album: album.title.clone(),
             artist: album.artist.clone(),
-            year: album.year.clone(),
+            artist_slug: slugs.artist(&artist.name)?.to_string(),
+            year: album.year.map(|y| y.to_string()).unwrap_or_default(),
             genre: album.genre.clone(),
+            // Genre pages come from track genres, which beets' album-level
+            // genre doesn't have to match
+            genre_slug: TermRef::new(&album.genre)
+                .map(|genre| genre.slug)
+                .filter(|slug| {
+                    album
+                        .tracks
+                        .iter()
+                        .filter_map(|track| TermRef::new(&track.genre))
+                        .any(|genre| &genre.slug == slug)
+                }),
+            label: TermRef::new(&album.label),
+            country: TermRef::new(&album.country),
+            composers,
+            games: self.soundtracks.games(album),
             tracktotal: album.tracktotal,
             tracks,
         };
@@ -343,15 +782,19 @@ impl Generator {
             .map_err(|e| anyhow::anyhow!("Failed to render album '{}': {}", album.title, e))?;
 
         // Write file
-        let path = format!("{}/albums/{}.md", self.output_dir, slug);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(MUSIC, format!("albums/{}.md", slug), content)?;
 
         Ok(())
     }
 
-    async fn generate_track_page(&self, track: &Track) -> Result<()> {
-        let slug = slugify(&track.title);
+    async fn generate_track_page(
+        &self,
+        artist: &Artist,
+        album: Option<&Album>,
+        track: &Track,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let slug = slugs.track(track)?;
 
         // Build search content
         let mut search_parts = Vec::new();
@@ -374,26 +817,33 @@ impl Generator {
             template: "track.html".to_string(),
             track: track.title.clone(),
             artist: track.artist.clone(),
+            artist_slug: slugs.artist(&artist.name)?.to_string(),
             album: if track.has_album() {
                 Some(track.album.clone())
             } else {
                 None
             },
-            year: if !track.year.is_empty() {
-                Some(track.year.clone())
-            } else {
-                None
-            },
+            album_slug: album
+                .map(|album| slugs.album(album).map(String::from))
+                .transpose()?,
+            year: track.year.map(|y| y.to_string()),
             format: track.format.clone(),
-            bitrate: track.bitrate.clone(),
-            length: track.length.clone(),
+            bitrate: track.bitrate_display(),
+            length: track.length_display(),
             genre: if !track.genre.is_empty() {
                 Some(track.genre.clone())
             } else {
                 None
             },
+            genre_slug: TermRef::new(&track.genre).map(|genre| genre.slug),
+            label: TermRef::new(&track.label),
+            composers: credits(&track.composer)
+                .into_iter()
+                .filter_map(TermRef::new)
+                .collect(),
+            country: TermRef::new(&track.country),
             comments: if !track.comments.is_empty() {
-                Some(track.comments.replace('\n', " ").replace('\r', " "))
+                Some(track.comments.replace(['\n', '\r'], " "))
             } else {
                 None
             },
@@ -408,9 +858,7 @@ impl Generator {
             .map_err(|e| anyhow::anyhow!("Failed to render track '{}': {}", track.title, e))?;
 
         // Write file
-        let path = format!("{}/tracks/{}.md", self.output_dir, slug);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(MUSIC, format!("tracks/{}.md", slug), content)?;
 
         Ok(())
     }
@@ -419,37 +867,123 @@ impl Generator {
         // Create games directory
         fs::create_dir_all(format!("{}/games", self.output_dir))?;
 
+        let slugs = SlugRegistry::from_games(library);
+        // One clock for the whole run, so the index's backlog counts match
+        // the lists they link to
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+
         // Generate games section index
-        self.generate_games_index(library).await?;
+        self.generate_games_index(library, &slugs, now).await?;
 
         // Generate individual game pages
         for game in library {
             if !game.is_empty() {
-                self.generate_game_page(game).await?;
+                self.generate_game_page(game, &slugs).await?;
             }
         }
 
+        self.generate_game_terms(&game_terms::GAME_GENRES, library, &slugs, |game| {
+            game.steam
+                .iter()
+                .flat_map(|steam| &steam.store)
+                .flat_map(|store| store.genres.iter().map(String::as_str))
+                .collect()
+        })
+        .await?;
+        self.generate_game_terms(&game_terms::DEVELOPERS, library, &slugs, |game| {
+            game.steam
+                .iter()
+                .flat_map(|steam| &steam.store)
+                .flat_map(|store| store.developers.iter().map(String::as_str))
+                .collect()
+        })
+        .await?;
+
+        self.generate_deck_list(library, &slugs).await?;
+        self.generate_recently_played_list(library, &slugs).await?;
+        self.generate_installed_list(library, &slugs).await?;
+        self.generate_never_played_list(library, &slugs).await?;
+        self.generate_barely_played_list(library, &slugs).await?;
+        self.generate_untouched_list(library, &slugs, now).await?;
+
         println!("Generated games in {}/games", self.output_dir);
         Ok(())
     }
 
-    async fn generate_games_index(&self, library: &GameLibrary) -> Result<()> {
+    async fn generate_games_index(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+        now: u64,
+    ) -> Result<()> {
         let game_count = library.len();
-        let total_playtime: u64 = library.iter().map(|g| g.playtime_forever).sum();
+        let total_playtime: u64 = library.iter().map(|g| g.playtime()).sum();
         let total_hours = total_playtime as f64 / 60.0;
 
         // Sort games by playtime (descending)
         let mut sorted_games = library.clone();
-        sorted_games.sort_by(|a, b| b.playtime_forever.cmp(&a.playtime_forever));
-
-        let games: Vec<GameLink> = sorted_games
-            .iter()
-            .map(|game| GameLink {
+        sorted_games.sort_by(|a, b| {
+            b.playtime()
+                .cmp(&a.playtime())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let link = |game: &Game| -> Result<GameLink> {
+            let steam = game.steam.as_ref();
+            Ok(GameLink {
                 name: game.name.clone(),
-                slug: slugify(&game.name),
+                slug: slugs.game(&game.key())?.to_string(),
                 playtime_hours: format!("{:.1}h", game.playtime_hours()),
+                achievements: steam
+                    .and_then(|s| s.achievements.as_ref())
+                    .map(|a| format!("{}/{} ({:.0}%)", a.unlocked(), a.total(), a.percent())),
+                thumbnail: steam
+                    .and_then(|s| s.artwork.as_ref())
+                    .and_then(|a| a.thumbnail())
+                    .map(String::from),
+                installed: steam.is_some_and(|s| s.installed.is_some()),
+                stores: game.owned.iter().map(|o| o.store.clone()).collect(),
+                rating: game.rating,
             })
+        };
+
+        let games: Vec<GameLink> = sorted_games.iter().map(link).collect::<Result<_>>()?;
+
+        // Completionist order: highest completion first, then most unlocked
+        let mut with_achievements: Vec<(&Game, &GameAchievements)> = library
+            .iter()
+            .filter_map(|game| Some((game, game.steam.as_ref()?.achievements.as_ref()?)))
             .collect();
+        with_achievements.sort_by(|(a, a_ach), (b, b_ach)| {
+            b_ach
+                .percent()
+                .total_cmp(&a_ach.percent())
+                .then_with(|| b_ach.unlocked().cmp(&a_ach.unlocked()))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let completionist: Vec<GameLink> = with_achievements
+            .into_iter()
+            .map(|(game, _)| link(game))
+            .collect::<Result<_>>()?;
+
+        // Statuses nobody uses are left out; games stay in playtime order
+        let mut status_sections = Vec::new();
+        for status in Status::ALL {
+            let games: Vec<GameLink> = sorted_games
+                .iter()
+                .filter(|game| game.status == Some(status))
+                .map(link)
+                .collect::<Result<_>>()?;
+            if !games.is_empty() {
+                status_sections.push(StatusSection {
+                    status: status.title().to_string(),
+                    games,
+                });
+            }
+        }
+
+        // Steam-only extras, summed over games owned there
+        let steam_games = || library.iter().filter_map(|game| game.steam.as_ref());
 
         let context = GamesIndexContext {
             title: "Games".to_string(),
@@ -457,7 +991,40 @@ impl Generator {
             template: "games_index.html".to_string(),
             game_count,
             total_hours: format!("{:.1}", total_hours),
+            platform_totals: PlatformPlaytime::list(platform_totals(library)),
+            deck_game_count: steam_games()
+                .filter(|game| game.playtime_deck_forever > 0)
+                .count(),
+            installed_count: steam_games().filter(|g| g.installed.is_some()).count(),
+            installed_size: InstallInfo {
+                size_on_disk: steam_games()
+                    .filter_map(|g| g.installed.as_ref())
+                    .map(|i| i.size_on_disk)
+                    .sum(),
+                ..InstallInfo::default()
+            }
+            .size_display(),
+            recent: steam_games()
+                .any(|game| game.recent_playtime.is_some())
+                .then(|| {
+                    let recent = steam_games().filter_map(|g| g.recent_playtime.as_ref());
+                    let (mut week, mut month, mut year) = (0, 0, 0);
+                    for r in recent {
+                        week += r.week;
+                        month += r.month;
+                        year += r.year;
+                    }
+                    RecentContext::new(week, month, year)
+                }),
+            recently_played_count: steam_games()
+                .filter(|game| game.recent_playtime.as_ref().is_some_and(|r| r.month > 0))
+                .count(),
+            backlog: BacklogSummary::new(library, &self.backlog, now),
+            store_counts: store_counts(library),
+            status_sections,
             games,
+            completionist,
+            has_store_details: steam_games().any(|game| game.store.is_some()),
         };
 
         let content = self
@@ -468,28 +1035,122 @@ impl Generator {
             )
             .map_err(|e| anyhow::anyhow!("Failed to render games index: {}", e))?;
 
-        let path = format!("{}/games/_index.md", self.output_dir);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(GAMES, "games/_index.md".to_string(), content)?;
 
         Ok(())
     }
 
-    async fn generate_game_page(&self, game: &SteamGame) -> Result<()> {
-        let slug = slugify(&game.name);
+    async fn generate_game_page(&self, game: &Game, slugs: &SlugRegistry) -> Result<()> {
+        let slug = slugs.game(&game.key())?;
+        let steam = game.steam.as_ref();
 
         // Build search content
-        let search_content = game.name.clone();
+        let mut search_content = game.name.clone();
+        if let Some(store) = steam.and_then(|s| s.store.as_ref()) {
+            for value in store.developers.iter().chain(&store.genres) {
+                search_content.push(' ');
+                search_content.push_str(value);
+            }
+        }
         let playtime_hours = format!("{:.1}", game.playtime_hours());
+        let artwork = steam.and_then(|s| s.artwork.as_ref());
 
         // Create context
         let context = GameContext {
             title: game.name.clone(),
             template: "game.html".to_string(),
             game: game.name.clone(),
-            appid: game.appid,
+            appid: game.appid(),
+            shortcut: steam.is_some_and(SteamGame::is_shortcut),
             playtime_hours,
             last_played: game.last_played_date(),
+            owned: game
+                .owned
+                .iter()
+                .map(|owned| OwnershipContext {
+                    store: owned.store.clone(),
+                    hours: format!("{:.1}", owned.playtime as f64 / 60.0),
+                    last_played: match owned.last_played {
+                        0 => "Never".to_string(),
+                        timestamp => format_date(timestamp),
+                    },
+                })
+                .collect(),
+            status: game.status.map(|status| status.title().to_string()),
+            rating: game.rating,
+            notes: game.notes.clone(),
+            platforms: steam
+                .map(|s| PlatformPlaytime::list(s.platform_playtime()))
+                .unwrap_or_default(),
+            installed: steam
+                .and_then(|s| s.installed.as_ref())
+                .map(|install| InstalledContext {
+                    size: install.size_display(),
+                    last_updated: if install.last_updated == 0 {
+                        "Unknown".to_string()
+                    } else {
+                        format_date(install.last_updated)
+                    },
+                    library: install.library.clone(),
+                }),
+            recent: steam
+                .and_then(|s| s.recent_playtime.as_ref())
+                .map(|r| RecentContext::new(r.week, r.month, r.year)),
+            playtime_history: steam
+                .and_then(|s| s.recent_playtime.as_ref())
+                .iter()
+                .flat_map(|r| &r.series)
+                .map(|point| PlaytimePointContext {
+                    date: point.date.to_string(),
+                    hours: format!("{:.1}", point.minutes as f64 / 60.0),
+                })
+                .collect(),
+            achievements: steam.and_then(|s| s.achievements.as_ref()).map(|a| {
+                AchievementsContext {
+                    unlocked: a.unlocked(),
+                    total: a.total(),
+                    percent: format!("{:.0}", a.percent()),
+                    unlocks: a
+                        .unlocked_by_date()
+                        .into_iter()
+                        .map(|achievement| AchievementUnlock {
+                            name: if achievement.name.is_empty() {
+                                achievement.apiname.clone()
+                            } else {
+                                achievement.name.clone()
+                            },
+                            description: achievement.description.clone(),
+                            date: format_date(achievement.unlocktime),
+                        })
+                        .collect(),
+                }
+            }),
+            store: steam
+                .and_then(|s| s.store.as_ref())
+                .map(|store| StoreContext {
+                    short_description: store.short_description.clone(),
+                    developers: store
+                        .developers
+                        .iter()
+                        .filter_map(|d| TermRef::new(d))
+                        .collect(),
+                    publishers: store.publishers.clone(),
+                    genres: store
+                        .genres
+                        .iter()
+                        .filter_map(|g| TermRef::new(g))
+                        .collect(),
+                    categories: store.categories.clone(),
+                    release_date: if store.coming_soon {
+                        format!("{} (coming soon)", store.release_date)
+                    } else {
+                        store.release_date.clone()
+                    },
+                }),
+            soundtracks: self.soundtracks.albums(game),
+            icon: artwork.and_then(|a| a.icon.clone()),
+            header_image: artwork.and_then(|a| a.header.clone()),
+            capsule_image: artwork.and_then(|a| a.capsule.clone()),
             search_content,
             url: format!("/games/{}", slug),
         };
@@ -501,9 +1162,7 @@ impl Generator {
             .map_err(|e| anyhow::anyhow!("Failed to render game '{}': {}", game.name, e))?;
 
         // Write file
-        let path = format!("{}/games/{}.md", self.output_dir, slug);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_page(GAMES, format!("games/{}.md", slug), content)?;
 
         Ok(())
     }
@@ -515,9 +1174,7 @@ sort_by = "title"
 template = "section.html"
 +++
 "#;
-        let path = format!("{}/artists/_index.md", self.output_dir);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_section_index("artists/_index.md", content)?;
         Ok(())
     }
 
@@ -528,9 +1185,7 @@ sort_by = "title"
 template = "section.html"
 +++
 "#;
-        let path = format!("{}/albums/_index.md", self.output_dir);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_section_index("albums/_index.md", content)?;
         Ok(())
     }
 
@@ -541,9 +1196,239 @@ sort_by = "title"
 template = "section.html"
 +++
 "#;
-        let path = format!("{}/tracks/_index.md", self.output_dir);
-        fs::write(&path, content)
-            .map_err(|e| anyhow::anyhow!("Failed to write file '{}': {}", path, e))?;
+        self.write_section_index("tracks/_index.md", content)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{self, TestSite};
+    use super::*;
+    use crate::parser::Parser;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn record(
+        id: u32,
+        title: &str,
+        artist: &str,
+        album: &str,
+        album_id: &str,
+    ) -> serde_json::Value {
+        json!({
+            "id": id.to_string(),
+            "title": title,
+            "artist": artist,
+            "albumartist": artist,
+            "album": album,
+            "album_id": album_id,
+            "year": "2012",
+            "genre": if id.is_multiple_of(2) { "Post-Rock" } else { "Math Rock" },
+            "length": "2:53",
+            "track": (id % 5 + 1).to_string(),
+            "bitrate": "320kbps",
+            "format": "MP3",
+        })
+    }
+
+    // Relative path -> contents for every file under `dir`
+    fn read_tree(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+        let mut tree = BTreeMap::new();
+        for entry in walkdir::WalkDir::new(dir) {
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                let relative = entry.path().strip_prefix(dir).unwrap();
+                tree.insert(
+                    relative.to_string_lossy().into_owned(),
+                    fs::read(entry.path()).unwrap(),
+                );
+            }
+        }
+        tree
+    }
+
+    async fn generate_into(export: &Path, site: &TestSite) {
+        // A fresh parser per run, so hash-based containers get new seeds
+        let library = Parser::new()
+            .parse_file(export.to_str().unwrap())
+            .await
+            .unwrap();
+        site.generate(&library).await;
+    }
+
+    #[tokio::test]
+    async fn identical_input_generates_identical_output() {
+        let mut records = Vec::new();
+        for (i, artist) in ["LITE", "Lamp", "toe", "Mouse on the Keys"]
+            .iter()
+            .enumerate()
+        {
+            let i = i as u32 * 10;
+            records.push(record(
+                i + 1,
+                "Theme",
+                artist,
+                "Past",
+                &format!("{}", i + 1),
+            ));
+            records.push(record(
+                i + 2,
+                "Interlude",
+                artist,
+                "Past",
+                &format!("{}", i + 1),
+            ));
+            records.push(record(
+                i + 3,
+                "Interlude",
+                artist,
+                "Past",
+                &format!("{}", i + 1),
+            ));
+            records.push(record(
+                i + 4,
+                "Theme",
+                artist,
+                "Future",
+                &format!("{}", i + 2),
+            ));
+            records.push(record(i + 5, "Theme", artist, "", ""));
+        }
+
+        let export_dir = tempfile::tempdir().unwrap();
+        let export = export_dir.path().join("export.json");
+        fs::write(&export, serde_json::to_string(&records).unwrap()).unwrap();
+
+        let first = TestSite::new();
+        let second = TestSite::new();
+        generate_into(&export, &first).await;
+        generate_into(&export, &second).await;
+
+        let first_tree = read_tree(&first.content());
+        let second_tree = read_tree(&second.content());
+        // Indexes, artists, albums and tracks; genre pages and their index;
+        // one year, its decade and the timeline; empty label, composer and
+        // country indexes
+        assert_eq!(first_tree.len(), 4 + 4 + 8 + 20 + 3 + 3 + 3);
+        assert_eq!(first_tree, second_tree);
+    }
+
+    fn games(names: &[&str]) -> GameLibrary {
+        names
+            .iter()
+            .map(|name| testing::game(name, "GOG", 0, 0))
+            .collect()
+    }
+
+    async fn generate_games_into(site: &TestSite, names: &[&str], prune: bool, dry_run: bool) {
+        let generator = site.generator();
+        generator.generate_games(&games(names)).await.unwrap();
+        generator.finish(prune, dry_run).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn prunes_only_stale_generated_files() {
+        let site = TestSite::new();
+        let pages = site.path("games");
+        generate_games_into(&site, &["Celeste", "Hades"], false, false).await;
+        fs::write(pages.join("notes.md"), "Written by hand").unwrap();
+
+        // Dry runs delete nothing and keep the stale page in the manifest
+        generate_games_into(&site, &["Celeste"], true, true).await;
+        assert!(pages.join("hades.md").exists());
+        let manifest = Manifest::load(&site.manifest_path()).unwrap();
+        assert!(manifest.contains("games/hades.md"));
+
+        generate_games_into(&site, &["Celeste"], true, false).await;
+        assert!(!pages.join("hades.md").exists());
+        assert!(pages.join("celeste.md").exists());
+        assert!(pages.join("notes.md").exists());
+        let manifest = Manifest::load(&site.manifest_path()).unwrap();
+        assert!(!manifest.contains("games/hades.md"));
+        assert!(!manifest.contains("games/notes.md"));
+    }
+
+    #[tokio::test]
+    async fn missing_manifest_prunes_nothing_and_corrupt_one_fails() {
+        let site = TestSite::new();
+        let pages = site.path("games");
+        fs::create_dir_all(&pages).unwrap();
+        fs::write(pages.join("old.md"), "From before the manifest").unwrap();
+
+        generate_games_into(&site, &["Celeste"], true, false).await;
+        assert!(pages.join("old.md").exists());
+
+        fs::write(site.manifest_path(), "{ not json").unwrap();
+        let result = Generator::new(
+            site.content().to_str().unwrap().to_string(),
+            site.manifest_path(),
+        );
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains("Failed to read manifest"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn adopts_boilerplate_section_indexes_from_before_the_manifest() {
+        let export_dir = tempfile::tempdir().unwrap();
+        let export = export_dir.path().join("export.json");
+        let records = vec![record(1, "Theme", "toe", "Past", "1")];
+        fs::write(&export, serde_json::to_string(&records).unwrap()).unwrap();
+
+        let site = TestSite::new();
+        generate_into(&export, &site).await;
+        fs::write(
+            site.path("albums/_index.md"),
+            "+++\ntitle = \"Records\"\n+++\n",
+        )
+        .unwrap();
+        // As if the manifest had never been written
+        fs::remove_file(site.manifest_path()).unwrap();
+
+        generate_into(&export, &site).await;
+        let manifest = Manifest::load(&site.manifest_path()).unwrap();
+        assert!(manifest.contains("artists/_index.md"));
+        assert!(!manifest.contains("albums/_index.md"));
+        assert!(site.read("albums/_index.md").contains("Records"));
+    }
+
+    #[tokio::test]
+    async fn identical_runs_leave_files_untouched() {
+        let site = TestSite::new();
+        generate_games_into(&site, &["Celeste", "Hades"], false, false).await;
+        let page = site.path("games/celeste.md");
+        let modified = |path: &Path| fs::metadata(path).unwrap().modified().unwrap();
+        let written = modified(&page);
+        let manifest_written = modified(&site.manifest_path());
+        let manifest = fs::read_to_string(site.manifest_path()).unwrap();
+
+        let generator = site.generator();
+        generator
+            .generate_games(&games(&["Celeste", "Hades"]))
+            .await
+            .unwrap();
+        generator.finish(false, false).await.unwrap();
+
+        let stats = generator.stats.lock().unwrap();
+        assert_eq!((stats.created, stats.updated), (0, 0));
+        assert!(stats.unchanged > 0);
+        assert_eq!(modified(&page), written);
+        assert_eq!(modified(&site.manifest_path()), manifest_written);
+        assert_eq!(fs::read_to_string(site.manifest_path()).unwrap(), manifest);
+    }
+
+    #[tokio::test]
+    async fn unreadable_existing_files_are_errors() {
+        let site = TestSite::new();
+        // A directory where a page should be can't be read as one
+        fs::create_dir_all(site.path("games/celeste.md")).unwrap();
+
+        let error = site
+            .generator()
+            .generate_games(&games(&["Celeste"]))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Failed to read file"), "{}", error);
+    }
+}
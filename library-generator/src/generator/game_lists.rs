@@ -0,0 +1,466 @@
+use super::Generator;
+use crate::game::{Game, GameLibrary, InstallInfo, RecentPlaytime, Status, SteamGame, format_date};
+use crate::manifest::GAMES;
+use crate::slugs::SlugRegistry;
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+
+// Directory for generated game listings, one page per list
+const GAME_LISTS_DIR: &str = "game-lists";
+
+/// Cutoffs for the backlog lists of games bought and barely or never played.
+#[derive(Debug, Clone, Copy)]
+pub struct BacklogThresholds {
+    // Games played for less than this count as barely played
+    pub barely_played_minutes: u64,
+    // Games last played longer ago than this count as untouched
+    pub untouched_years: u32,
+}
+
+impl Default for BacklogThresholds {
+    fn default() -> Self {
+        Self {
+            barely_played_minutes: 60,
+            untouched_years: 2,
+        }
+    }
+}
+
+impl BacklogThresholds {
+    fn never_played(&self, game: &Game) -> bool {
+        on_backlog(game) && game.playtime() == 0 && game.last_played() == 0
+    }
+
+    fn barely_played(&self, game: &Game) -> bool {
+        on_backlog(game) && game.playtime() > 0 && game.playtime() < self.barely_played_minutes
+    }
+
+    // Games without a last played time can't tell how long it's been
+    fn untouched(&self, game: &Game, now: u64) -> bool {
+        let cutoff = now.saturating_sub(self.untouched_years as u64 * 365 * 24 * 60 * 60);
+        on_backlog(game) && game.last_played() > 0 && game.last_played() < cutoff
+    }
+
+    fn untouched_description(&self) -> String {
+        match self.untouched_years {
+            1 => "a year".to_string(),
+            years => format!("{} years", years),
+        }
+    }
+
+    fn barely_played_description(&self) -> String {
+        match self.barely_played_minutes {
+            60 => "an hour".to_string(),
+            minutes if minutes % 60 == 0 => format!("{} hours", minutes / 60),
+            minutes => format!("{} minutes", minutes),
+        }
+    }
+}
+
+// Games the overlay marks as played through, being played or given up on
+// are never part of the shame pile
+fn on_backlog(game: &Game) -> bool {
+    game.status.is_none_or(|status| status == Status::Backlog)
+}
+
+/// How much of the library has been played, for the games index.
+#[derive(Serialize)]
+pub(super) struct BacklogSummary {
+    played_count: usize,
+    played_percent: String,
+    never_played_count: usize,
+    barely_played_count: usize,
+    barely_played: String,
+    untouched_count: usize,
+    untouched: String,
+}
+
+impl BacklogSummary {
+    pub(super) fn new(library: &GameLibrary, thresholds: &BacklogThresholds, now: u64) -> Self {
+        let played_count = library
+            .iter()
+            .filter(|game| game.playtime() > 0 || game.last_played() > 0 || !on_backlog(game))
+            .count();
+
+        Self {
+            played_count,
+            played_percent: format!(
+                "{:.0}",
+                played_count as f64 * 100.0 / library.len().max(1) as f64
+            ),
+            never_played_count: library
+                .iter()
+                .filter(|game| thresholds.never_played(game))
+                .count(),
+            barely_played_count: library
+                .iter()
+                .filter(|game| thresholds.barely_played(game))
+                .count(),
+            barely_played: thresholds.barely_played_description(),
+            untouched_count: library
+                .iter()
+                .filter(|game| thresholds.untouched(game, now))
+                .count(),
+            untouched: thresholds.untouched_description(),
+        }
+    }
+}
+
+// Context structs for game list page template
+#[derive(Serialize)]
+struct GameListContext {
+    title: String,
+    template: String,
+    description: String,
+    game_count: usize,
+    games: Vec<GameListEntry>,
+}
+
+#[derive(Serialize)]
+struct GameListEntry {
+    name: String,
+    slug: String,
+    detail: String,
+}
+
+// Stores a game is owned on, for list details
+fn stores(game: &Game) -> String {
+    let stores: Vec<&str> = game.owned.iter().map(|o| o.store.as_str()).collect();
+    stores.join(", ")
+}
+
+impl Generator {
+    /// Games with Steam Deck playtime, most played on the Deck first.
+    pub(super) async fn generate_deck_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let mut games: Vec<(&Game, &SteamGame)> = library
+            .iter()
+            .filter_map(|game| Some((game, game.steam.as_ref()?)))
+            .filter(|(_, steam)| steam.playtime_deck_forever > 0)
+            .collect();
+        games.sort_by(|(a, a_steam), (b, b_steam)| {
+            b_steam
+                .playtime_deck_forever
+                .cmp(&a_steam.playtime_deck_forever)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let entries = games
+            .iter()
+            .map(|(game, steam)| {
+                let deck = steam.playtime_deck_forever as f64;
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!(
+                        "{:.1}h on Deck ({:.0}% of {:.1}h)",
+                        deck / 60.0,
+                        deck * 100.0 / steam.playtime_forever.max(1) as f64,
+                        steam.playtime_hours()
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "played-on-deck",
+            "Played on Steam Deck",
+            "Games with playtime on the Steam Deck, by hours played there.",
+            entries,
+        )
+        .await
+    }
+
+    /// Games played in the last 30 days, by hours this week then this month.
+    pub(super) async fn generate_recently_played_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let mut games: Vec<(&Game, &RecentPlaytime)> = library
+            .iter()
+            .filter_map(|game| Some((game, game.steam.as_ref()?.recent_playtime.as_ref()?)))
+            .filter(|(_, recent)| recent.month > 0)
+            .collect();
+        games.sort_by(|(a, a_recent), (b, b_recent)| {
+            b_recent
+                .week
+                .cmp(&a_recent.week)
+                .then_with(|| b_recent.month.cmp(&a_recent.month))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let entries = games
+            .iter()
+            .map(|(game, recent)| {
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!(
+                        "{:.1}h this week, {:.1}h this month",
+                        recent.week as f64 / 60.0,
+                        recent.month as f64 / 60.0
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "recently-played",
+            "Recently Played",
+            "Games played in the last 30 days, from playtime snapshots taken on each Steam fetch.",
+            entries,
+        )
+        .await
+    }
+
+    /// Installed games, largest first.
+    pub(super) async fn generate_installed_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let mut games: Vec<(&Game, &InstallInfo)> = library
+            .iter()
+            .filter_map(|game| Some((game, game.steam.as_ref()?.installed.as_ref()?)))
+            .collect();
+        games.sort_by(|(a, a_install), (b, b_install)| {
+            b_install
+                .size_on_disk
+                .cmp(&a_install.size_on_disk)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let entries = games
+            .iter()
+            .map(|(game, install)| {
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!(
+                        "{}, {:.1}h played",
+                        install.size_display(),
+                        game.playtime_hours()
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "installed",
+            "Installed",
+            "Games installed in the local Steam libraries, largest first.",
+            entries,
+        )
+        .await
+    }
+
+    /// Owned games with no playtime at all, by name.
+    pub(super) async fn generate_never_played_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let mut games: Vec<&Game> = library
+            .iter()
+            .filter(|game| self.backlog.never_played(game))
+            .collect();
+        games.sort_by_key(|game| game.name.to_lowercase());
+
+        let entries = games
+            .iter()
+            .map(|game| {
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!("owned on {}", stores(game)),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "never-played",
+            "Never Played",
+            "Games in the library that have never been started.",
+            entries,
+        )
+        .await
+    }
+
+    /// Games started but played for less than the threshold, least first.
+    pub(super) async fn generate_barely_played_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let mut games: Vec<&Game> = library
+            .iter()
+            .filter(|game| self.backlog.barely_played(game))
+            .collect();
+        games.sort_by(|a, b| {
+            a.playtime()
+                .cmp(&b.playtime())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let entries = games
+            .iter()
+            .map(|game| {
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!("{} min on {}", game.playtime(), stores(game)),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "barely-played",
+            "Barely Played",
+            &format!(
+                "Games started but played for less than {}.",
+                self.backlog.barely_played_description()
+            ),
+            entries,
+        )
+        .await
+    }
+
+    /// Games not played for longer than the threshold, longest ago first.
+    pub(super) async fn generate_untouched_list(
+        &self,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+        now: u64,
+    ) -> Result<()> {
+        let mut games: Vec<&Game> = library
+            .iter()
+            .filter(|game| self.backlog.untouched(game, now))
+            .collect();
+        games.sort_by(|a, b| {
+            a.last_played()
+                .cmp(&b.last_played())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let entries = games
+            .iter()
+            .map(|game| {
+                Ok(GameListEntry {
+                    name: game.name.clone(),
+                    slug: slugs.game(&game.key())?.to_string(),
+                    detail: format!(
+                        "last played {}, {:.1}h played",
+                        format_date(game.last_played()),
+                        game.playtime_hours()
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.generate_game_list(
+            "untouched",
+            "Untouched",
+            &format!(
+                "Games not played in over {}, by when they were last played.",
+                self.backlog.untouched_description()
+            ),
+            entries,
+        )
+        .await
+    }
+
+    /// Write one listing page. Empty lists aren't written, so a list that
+    /// empties out is pruned like any other page that stops being generated.
+    async fn generate_game_list(
+        &self,
+        name: &str,
+        title: &str,
+        description: &str,
+        games: Vec<GameListEntry>,
+    ) -> Result<()> {
+        if games.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(format!("{}/{}", self.output_dir, GAME_LISTS_DIR))?;
+
+        let context = GameListContext {
+            title: title.to_string(),
+            template: "game_list.html".to_string(),
+            description: description.to_string(),
+            game_count: games.len(),
+            games,
+        };
+
+        let content = self
+            .tera
+            .render(
+                "game_list.md.tera",
+                &tera::Context::from_serialize(&context)?,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to render game list '{}': {}", name, e))?;
+
+        self.write_page(GAMES, format!("{}/{}.md", GAME_LISTS_DIR, name), content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::testing::{self, TestSite};
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn game(name: &str, playtime: u64, last_played: u64) -> Game {
+        testing::game(name, "GOG", playtime, last_played)
+    }
+
+    #[tokio::test]
+    async fn generates_backlog_lists_and_summary() {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let library = vec![
+            game("Celeste", 600, now - 10 * DAY),
+            game("Hades", 0, 0),
+            game("Tunic", 45, now - 1000 * DAY),
+            game("Outer Wilds", 300, now - 800 * DAY),
+            Game {
+                status: Some(Status::Abandoned),
+                ..game("Spelunky", 0, 0)
+            },
+        ];
+
+        let site = TestSite::new();
+        let generator = site.generator().with_backlog_thresholds(BacklogThresholds {
+            barely_played_minutes: 120,
+            untouched_years: 2,
+        });
+        generator.generate_games(&library).await.unwrap();
+
+        let never = site.read("game-lists/never-played.md");
+        assert!(never.contains("[Hades]"));
+        assert!(!never.contains("Spelunky"));
+
+        let barely = site.read("game-lists/barely-played.md");
+        assert!(barely.contains("less than 2 hours"));
+        assert!(barely.contains("45 min on GOG"));
+        assert!(!barely.contains("Hades"));
+
+        let untouched = site.read("game-lists/untouched.md");
+        let tunic = untouched.find("[Tunic]").unwrap();
+        let outer_wilds = untouched.find("[Outer Wilds]").unwrap();
+        assert!(tunic < outer_wilds, "{}", untouched);
+        assert!(!untouched.contains("Celeste"));
+
+        let index = site.read("games/_index.md");
+        assert!(index.contains("**80%** of the library played (4 of 5 games)"));
+        assert!(index.contains("[1 never played](/game-lists/never-played/)"));
+        assert!(index.contains("[2 not played in over 2 years](/game-lists/untouched/)"));
+    }
+}
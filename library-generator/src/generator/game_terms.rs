@@ -0,0 +1,175 @@
+use super::Generator;
+use super::terms::TermSection;
+use crate::game::{Game, GameLibrary};
+use crate::manifest::GAMES;
+use crate::slugs::SlugRegistry;
+use crate::taxonomy::{self, GameTerm};
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+
+pub(super) const GAME_GENRES: TermSection = TermSection {
+    dir: "game-genres",
+    title: "Game Genres",
+    kind: "Genre",
+};
+
+pub(super) const DEVELOPERS: TermSection = TermSection {
+    dir: "developers",
+    title: "Developers",
+    kind: "Developer",
+};
+
+// Context structs for game term page template
+#[derive(Serialize)]
+struct GameTermContext {
+    title: String,
+    template: String,
+    kind: String,
+    section: String,
+    section_title: String,
+    game_count: usize,
+    total_hours: String,
+    games: Vec<GameTermGame>,
+}
+
+#[derive(Serialize)]
+struct GameTermGame {
+    name: String,
+    slug: String,
+    playtime_hours: String,
+}
+
+// Context structs for game term section index template
+#[derive(Serialize)]
+struct GameTermsIndexContext {
+    title: String,
+    sort_by: String,
+    template: String,
+    kind: String,
+    section: String,
+    terms: Vec<GameTermLink>,
+}
+
+#[derive(Serialize)]
+struct GameTermLink {
+    name: String,
+    slug: String,
+    game_count: usize,
+    total_hours: String,
+}
+
+fn total_hours(games: &[&Game]) -> String {
+    let minutes: u64 = games.iter().map(|g| g.playtime()).sum();
+    format!("{:.1}", minutes as f64 / 60.0)
+}
+
+impl Generator {
+    /// Pages grouping games by a store field. Nothing is written when no
+    /// game has a value for it, e.g. when store details weren't fetched.
+    pub(super) async fn generate_game_terms(
+        &self,
+        section: &TermSection,
+        library: &GameLibrary,
+        slugs: &SlugRegistry,
+        field: impl Fn(&Game) -> Vec<&str>,
+    ) -> Result<()> {
+        let terms = taxonomy::collect_games(library, field);
+        if terms.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(format!("{}/{}", self.output_dir, section.dir))?;
+
+        self.generate_game_terms_index(section, &terms).await?;
+        for term in &terms {
+            self.generate_game_term_page(section, term, slugs).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn generate_game_terms_index(
+        &self,
+        section: &TermSection,
+        terms: &[GameTerm<'_>],
+    ) -> Result<()> {
+        let context = GameTermsIndexContext {
+            title: section.title.to_string(),
+            sort_by: "title".to_string(),
+            template: "game_terms_index.html".to_string(),
+            kind: section.kind.to_string(),
+            section: section.dir.to_string(),
+            terms: terms
+                .iter()
+                .map(|term| GameTermLink {
+                    name: term.name.clone(),
+                    slug: term.slug.clone(),
+                    game_count: term.games.len(),
+                    total_hours: total_hours(&term.games),
+                })
+                .collect(),
+        };
+
+        let content = self
+            .tera
+            .render(
+                "game_terms_index.md.tera",
+                &tera::Context::from_serialize(&context)?,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to render {} index: {}", section.dir, e))?;
+
+        self.write_page(GAMES, format!("{}/_index.md", section.dir), content)?;
+
+        Ok(())
+    }
+
+    async fn generate_game_term_page(
+        &self,
+        section: &TermSection,
+        term: &GameTerm<'_>,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        // Most played first, like the games index
+        let mut games = term.games.clone();
+        games.sort_by(|a, b| {
+            b.playtime()
+                .cmp(&a.playtime())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let context = GameTermContext {
+            title: term.name.clone(),
+            template: "game_term.html".to_string(),
+            kind: section.kind.to_string(),
+            section: section.dir.to_string(),
+            section_title: section.title.to_string(),
+            game_count: games.len(),
+            total_hours: total_hours(&games),
+            games: games
+                .iter()
+                .map(|game| {
+                    Ok(GameTermGame {
+                        name: game.name.clone(),
+                        slug: slugs.game(&game.key())?.to_string(),
+                        playtime_hours: format!("{:.1}h", game.playtime_hours()),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let content = self
+            .tera
+            .render(
+                "game_term.md.tera",
+                &tera::Context::from_serialize(&context)?,
+            )
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to render {} '{}': {}", section.kind, term.name, e)
+            })?;
+
+        self.write_page(GAMES, format!("{}/{}.md", section.dir, term.slug), content)?;
+
+        Ok(())
+    }
+}
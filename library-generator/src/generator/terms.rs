@@ -0,0 +1,276 @@
+use super::{ArtistLink, Generator};
+use crate::library::Library;
+use crate::manifest::MUSIC;
+use crate::slugs::SlugRegistry;
+use crate::taxonomy::{self, Term};
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+
+/// A generated section that groups the library by one track field.
+pub(super) struct TermSection {
+    pub dir: &'static str,
+    pub title: &'static str,
+    pub kind: &'static str,
+}
+
+pub(super) const GENRES: TermSection = TermSection {
+    dir: "genres",
+    title: "Genres",
+    kind: "Genre",
+};
+
+pub(super) const LABELS: TermSection = TermSection {
+    dir: "labels",
+    title: "Labels",
+    kind: "Label",
+};
+
+pub(super) const COMPOSERS: TermSection = TermSection {
+    dir: "composers",
+    title: "Composers",
+    kind: "Composer",
+};
+
+pub(super) const COUNTRIES: TermSection = TermSection {
+    dir: "countries",
+    title: "Countries",
+    kind: "Country",
+};
+
+// Context structs for term page template
+#[derive(Serialize)]
+struct TermContext {
+    title: String,
+    template: String,
+    kind: String,
+    section: String,
+    section_title: String,
+    artist_count: usize,
+    album_count: usize,
+    track_count: usize,
+    artists: Vec<ArtistLink>,
+    albums: Vec<TermAlbum>,
+    tracks: Vec<TermTrack>,
+}
+
+#[derive(Serialize)]
+struct TermAlbum {
+    title: String,
+    slug: String,
+    artist: String,
+    year: String,
+}
+
+#[derive(Serialize)]
+struct TermTrack {
+    title: String,
+    slug: String,
+    artist: String,
+}
+
+// Context structs for term section index template
+#[derive(Serialize)]
+struct TermsIndexContext {
+    title: String,
+    sort_by: String,
+    template: String,
+    kind: String,
+    section: String,
+    terms: Vec<TermLink>,
+}
+
+#[derive(Serialize)]
+struct TermLink {
+    name: String,
+    slug: String,
+    artist_count: usize,
+    album_count: usize,
+    track_count: usize,
+}
+
+impl Generator {
+    pub(super) async fn generate_terms(
+        &self,
+        section: &TermSection,
+        library: &Library,
+        slugs: &SlugRegistry,
+        field: impl Fn(&crate::track::Track) -> Vec<&str>,
+    ) -> Result<()> {
+        fs::create_dir_all(format!("{}/{}", self.output_dir, section.dir))?;
+
+        let terms = taxonomy::collect(library, field);
+
+        self.generate_terms_index(section, &terms).await?;
+        for term in &terms {
+            self.generate_term_page(section, term, slugs).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn generate_terms_index(&self, section: &TermSection, terms: &[Term<'_>]) -> Result<()> {
+        let context = TermsIndexContext {
+            title: section.title.to_string(),
+            sort_by: "title".to_string(),
+            template: "terms_index.html".to_string(),
+            kind: section.kind.to_string(),
+            section: section.dir.to_string(),
+            terms: terms
+                .iter()
+                .map(|term| TermLink {
+                    name: term.name.clone(),
+                    slug: term.slug.clone(),
+                    artist_count: term.artists.len(),
+                    album_count: term.albums.len(),
+                    track_count: term.tracks.len(),
+                })
+                .collect(),
+        };
+
+        let content = self
+            .tera
+            .render(
+                "terms_index.md.tera",
+                &tera::Context::from_serialize(&context)?,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to render {} index: {}", section.dir, e))?;
+
+        self.write_page(MUSIC, format!("{}/_index.md", section.dir), content)?;
+
+        Ok(())
+    }
+
+    async fn generate_term_page(
+        &self,
+        section: &TermSection,
+        term: &Term<'_>,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        let context = TermContext {
+            title: term.name.clone(),
+            template: "term.html".to_string(),
+            kind: section.kind.to_string(),
+            section: section.dir.to_string(),
+            section_title: section.title.to_string(),
+            artist_count: term.artists.len(),
+            album_count: term.albums.len(),
+            track_count: term.tracks.len(),
+            artists: term
+                .artists
+                .iter()
+                .map(|artist| {
+                    Ok(ArtistLink {
+                        name: artist.name.clone(),
+                        slug: slugs.artist(&artist.name)?.to_string(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            albums: term
+                .albums
+                .iter()
+                .map(|(artist, album)| {
+                    Ok(TermAlbum {
+                        title: album.title.clone(),
+                        slug: slugs.album(album)?.to_string(),
+                        artist: artist.name.clone(),
+                        year: album.year.map(|y| y.to_string()).unwrap_or_default(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            tracks: term
+                .tracks
+                .iter()
+                .map(|(_, _, track)| {
+                    Ok(TermTrack {
+                        title: track.title.clone(),
+                        slug: slugs.track(track)?.to_string(),
+                        artist: track.artist.clone(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let content = self
+            .tera
+            .render("term.md.tera", &tera::Context::from_serialize(&context)?)
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to render {} '{}': {}", section.kind, term.name, e)
+            })?;
+
+        self.write_page(MUSIC, format!("{}/{}.md", section.dir, term.slug), content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::testing::TestSite;
+    use crate::library::{Album, Artist, Library};
+    use crate::track::Track;
+
+    fn track(id: &str, title: &str, composer: &str) -> Track {
+        Track {
+            id: id.to_string(),
+            title: title.to_string(),
+            artist: "toe".to_string(),
+            album: "Past".to_string(),
+            albumartist: "toe".to_string(),
+            composer: composer.to_string(),
+            label: "Topshelf Records".to_string(),
+            country: "JP".to_string(),
+            ..Track::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn generates_label_composer_and_country_pages() {
+        let mut album = Album::new("1".to_string(), "Past".to_string(), "toe".to_string());
+        album.label = "Topshelf Records".to_string();
+        album.country = "JP".to_string();
+        album.add_track(track("1", "Theme", "Takaaki Mino; Kashikura Takashi"));
+        album.add_track(track(
+            "2",
+            "Interlude",
+            "Kashikura Takashi; Yamazaki Hirokazu",
+        ));
+        album.add_track(track("4", "Cover", "Lennon, John"));
+        album.add_track(track("3", "Outro", ""));
+        let mut toe = Artist::new("toe".to_string());
+        toe.add_album(album);
+        let library = Library::from([("toe".to_string(), toe)]);
+
+        let site = TestSite::new();
+        site.generate(&library).await;
+
+        let label = site.read("labels/topshelf-records.md");
+        assert!(label.contains("[Past]"), "{}", label);
+        assert!(site.path("countries/jp.md").exists());
+
+        let composers = site.read("composers/_index.md");
+        for composer in ["Takaaki Mino", "Kashikura Takashi", "Yamazaki Hirokazu"] {
+            assert!(composers.contains(composer), "{}", composers);
+        }
+        assert!(
+            !site
+                .path("composers/takaaki-mino-kashikura-takashi.md")
+                .exists()
+        );
+        // Commas are part of a name
+        assert!(site.path("composers/lennon-john.md").exists());
+        assert!(!site.path("composers/john.md").exists());
+        let kashikura = site.read("composers/kashikura-takashi.md");
+        assert!(kashikura.contains("[Theme]") && kashikura.contains("[Interlude]"));
+
+        let theme = site.read("tracks/theme.md");
+        assert!(theme.contains(
+            "**Composers:** [Takaaki Mino](/composers/takaaki-mino/), \
+             [Kashikura Takashi](/composers/kashikura-takashi/)"
+        ));
+        let album = site.read("albums/past.md");
+        assert!(album.contains("**Label:** [Topshelf Records](/labels/topshelf-records/)"));
+        assert!(album.contains("**Country:** [JP](/countries/jp/)"));
+        assert_eq!(album.matches("(/composers/").count(), 4, "{}", album);
+    }
+}
@@ -0,0 +1,89 @@
+//! A throwaway site to generate into from tests, and the games they use.
+
+use super::Generator;
+use crate::game::{Game, GameLibrary, Ownership, SteamGame};
+use crate::library::Library;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Content and manifest in a temporary directory, removed when dropped.
+pub struct TestSite {
+    dir: tempfile::TempDir,
+}
+
+impl TestSite {
+    pub fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    /// A generator for this site, as a fresh run would create it.
+    pub fn generator(&self) -> Generator {
+        Generator::new(
+            self.content().to_str().unwrap().to_string(),
+            self.manifest_path(),
+        )
+        .unwrap()
+    }
+
+    /// Generate music pages in one complete run.
+    pub async fn generate(&self, library: &Library) {
+        let generator = self.generator();
+        generator.generate(library).await.unwrap();
+        generator.finish(false, false).await.unwrap();
+    }
+
+    /// Generate game pages in one complete run.
+    pub async fn generate_games(&self, library: &GameLibrary) {
+        let generator = self.generator();
+        generator.generate_games(library).await.unwrap();
+        generator.finish(false, false).await.unwrap();
+    }
+
+    /// The temporary directory, for test inputs that live beside the site.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn content(&self) -> PathBuf {
+        self.root().join("content")
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root().join("manifest.json")
+    }
+
+    /// A generated file, relative to the content directory.
+    pub fn path(&self, relative: &str) -> PathBuf {
+        self.content().join(relative)
+    }
+
+    pub fn read(&self, relative: &str) -> String {
+        fs::read_to_string(self.path(relative))
+            .unwrap_or_else(|e| panic!("Failed to read '{}': {}", relative, e))
+    }
+}
+
+/// A Steam library entry with `minutes` of playtime.
+pub fn steam_game(appid: u64, name: &str, minutes: u64) -> SteamGame {
+    SteamGame {
+        appid,
+        name: name.to_string(),
+        playtime_forever: minutes,
+        ..SteamGame::default()
+    }
+}
+
+/// A game owned on one non-Steam store, using its name as the store ID.
+pub fn game(name: &str, store: &str, playtime: u64, last_played: u64) -> Game {
+    Game::new(
+        name.to_string(),
+        Ownership {
+            store: store.to_string(),
+            id: name.to_string(),
+            playtime,
+            last_played,
+        },
+    )
+}
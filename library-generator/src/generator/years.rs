@@ -0,0 +1,394 @@
+use super::Generator;
+use crate::library::{Album, Artist, Library};
+use crate::manifest::MUSIC;
+use crate::slugs::SlugRegistry;
+use crate::track::Track;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+// Widest bar drawn on the timeline index
+const TIMELINE_WIDTH: usize = 40;
+
+// Context struct for year and decade page template
+#[derive(Serialize)]
+struct PeriodContext {
+    title: String,
+    template: String,
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decade: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    years: Vec<YearLink>,
+    album_count: usize,
+    track_count: usize,
+    albums: Vec<PeriodAlbum>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tracks: Vec<PeriodTrack>,
+}
+
+#[derive(Serialize)]
+struct PeriodAlbum {
+    title: String,
+    slug: String,
+    artist: String,
+    artist_slug: String,
+    year: u32,
+}
+
+#[derive(Serialize)]
+struct PeriodTrack {
+    title: String,
+    slug: String,
+    artist: String,
+    album: String,
+    year: u32,
+}
+
+// Context structs for timeline index template
+#[derive(Serialize)]
+struct TimelineContext {
+    title: String,
+    sort_by: String,
+    template: String,
+    year_count: usize,
+    decades: Vec<DecadeLink>,
+}
+
+#[derive(Serialize)]
+struct DecadeLink {
+    name: String,
+    album_count: usize,
+    track_count: usize,
+    years: Vec<YearLink>,
+}
+
+#[derive(Serialize)]
+struct YearLink {
+    year: u32,
+    album_count: usize,
+    track_count: usize,
+    bar: String,
+}
+
+// Albums released in one year, and tracks released that year outside an
+// album of that year: standalone tracks, and album tracks dated on their own
+// such as a compilation's. Tracks keep their album's year, if any.
+#[derive(Default)]
+struct Period<'a> {
+    albums: Vec<(&'a Artist, &'a Album)>,
+    tracks: Vec<(&'a Track, Option<u32>)>,
+}
+
+impl Period<'_> {
+    fn track_count(&self) -> usize {
+        album_track_count(&self.albums) + self.tracks.len()
+    }
+}
+
+fn decade_of(year: u32) -> u32 {
+    year - year % 10
+}
+
+fn album_track_count(albums: &[(&Artist, &Album)]) -> usize {
+    albums.iter().map(|(_, album)| album.track_count()).sum()
+}
+
+// A decade's albums, and its tracks not already listed under one of them
+fn decade_entries<'a>(
+    decade: u32,
+    periods: &[(u32, &Period<'a>)],
+) -> (Vec<(&'a Artist, &'a Album)>, Vec<&'a Track>) {
+    let albums = periods
+        .iter()
+        .flat_map(|(_, p)| p.albums.iter().copied())
+        .collect();
+    let tracks = periods
+        .iter()
+        .flat_map(|(_, p)| p.tracks.iter())
+        .filter(|(_, album_year)| album_year.map(decade_of) != Some(decade))
+        .map(|(track, _)| *track)
+        .collect();
+    (albums, tracks)
+}
+
+impl Generator {
+    pub(super) async fn generate_years(
+        &self,
+        library: &Library,
+        slugs: &SlugRegistry,
+    ) -> Result<()> {
+        fs::create_dir_all(format!("{}/years", self.output_dir))?;
+
+        let mut years: BTreeMap<u32, Period> = BTreeMap::new();
+        for artist in library.values() {
+            for album in &artist.albums {
+                if let Some(year) = album.year {
+                    years.entry(year).or_default().albums.push((artist, album));
+                }
+                // Track pages link their own year, so it needs a page
+                for track in &album.tracks {
+                    if let Some(year) = track.year.filter(|&year| Some(year) != album.year) {
+                        years
+                            .entry(year)
+                            .or_default()
+                            .tracks
+                            .push((track, album.year));
+                    }
+                }
+            }
+            for track in &artist.tracks {
+                if let Some(year) = track.year {
+                    years.entry(year).or_default().tracks.push((track, None));
+                }
+            }
+        }
+
+        let widest = years
+            .values()
+            .map(|period| period.albums.len() + period.tracks.len())
+            .max()
+            .unwrap_or(0);
+        let year_link = |year: u32, period: &Period| {
+            let count = period.albums.len() + period.tracks.len();
+            YearLink {
+                year,
+                album_count: period.albums.len(),
+                track_count: period.track_count(),
+                bar: "█".repeat((count * TIMELINE_WIDTH).div_ceil(widest.max(1))),
+            }
+        };
+
+        let mut decades: BTreeMap<u32, Vec<(u32, &Period)>> = BTreeMap::new();
+        for (&year, period) in &years {
+            decades
+                .entry(decade_of(year))
+                .or_default()
+                .push((year, period));
+        }
+
+        // Timeline index, newest first
+        let context = TimelineContext {
+            title: "Years".to_string(),
+            sort_by: "title".to_string(),
+            template: "years_index.html".to_string(),
+            year_count: years.len(),
+            decades: decades
+                .iter()
+                .rev()
+                .map(|(&decade, periods)| {
+                    let (albums, tracks) = decade_entries(decade, periods);
+                    DecadeLink {
+                        name: format!("{}s", decade),
+                        album_count: albums.len(),
+                        track_count: album_track_count(&albums) + tracks.len(),
+                        years: periods
+                            .iter()
+                            .rev()
+                            .map(|(year, period)| year_link(*year, period))
+                            .collect(),
+                    }
+                })
+                .collect(),
+        };
+
+        let content = self
+            .tera
+            .render(
+                "years_index.md.tera",
+                &tera::Context::from_serialize(&context)?,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to render years index: {}", e))?;
+        self.write_page(MUSIC, "years/_index.md".to_string(), content)?;
+
+        // One page per year
+        for (&year, period) in &years {
+            let context = PeriodContext {
+                title: year.to_string(),
+                template: "year.html".to_string(),
+                kind: "year".to_string(),
+                decade: Some(format!("{}s", decade_of(year))),
+                years: Vec::new(),
+                album_count: period.albums.len(),
+                track_count: period.track_count(),
+                albums: period_albums(period.albums.iter().copied(), slugs)?,
+                tracks: period_tracks(period.tracks.iter().map(|(track, _)| *track), slugs)?,
+            };
+            self.write_period(&context, format!("years/{}.md", year))?;
+        }
+
+        // One page per decade
+        for (&decade, periods) in &decades {
+            let name = format!("{}s", decade);
+            let (albums, tracks) = decade_entries(decade, periods);
+            let context = PeriodContext {
+                title: name.clone(),
+                template: "year.html".to_string(),
+                kind: "decade".to_string(),
+                decade: None,
+                years: periods
+                    .iter()
+                    .map(|(year, period)| year_link(*year, period))
+                    .collect(),
+                album_count: albums.len(),
+                track_count: album_track_count(&albums) + tracks.len(),
+                albums: period_albums(albums.into_iter(), slugs)?,
+                tracks: period_tracks(tracks.into_iter(), slugs)?,
+            };
+            self.write_period(&context, format!("years/{}.md", name))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_period(&self, context: &PeriodContext, relative: String) -> Result<()> {
+        let content = self
+            .tera
+            .render("year.md.tera", &tera::Context::from_serialize(context)?)
+            .map_err(|e| anyhow::anyhow!("Failed to render year '{}': {}", context.title, e))?;
+        self.write_page(MUSIC, relative, content)
+    }
+}
+
+// Albums sorted by year, then artist and title
+fn period_albums<'a>(
+    albums: impl Iterator<Item = (&'a Artist, &'a Album)>,
+    slugs: &SlugRegistry,
+) -> Result<Vec<PeriodAlbum>> {
+    let mut albums: Vec<PeriodAlbum> = albums
+        .map(|(artist, album)| {
+            Ok(PeriodAlbum {
+                title: album.title.clone(),
+                slug: slugs.album(album)?.to_string(),
+                artist: artist.name.clone(),
+                artist_slug: slugs.artist(&artist.name)?.to_string(),
+                year: album.year.unwrap_or_default(),
+            })
+        })
+        .collect::<Result<_>>()?;
+    albums.sort_by(|a, b| {
+        (a.year, &a.artist, &a.title, &a.slug).cmp(&(b.year, &b.artist, &b.title, &b.slug))
+    });
+    Ok(albums)
+}
+
+fn period_tracks<'a>(
+    tracks: impl Iterator<Item = &'a Track>,
+    slugs: &SlugRegistry,
+) -> Result<Vec<PeriodTrack>> {
+    let mut tracks: Vec<PeriodTrack> = tracks
+        .map(|track| {
+            Ok(PeriodTrack {
+                title: track.title.clone(),
+                slug: slugs.track(track)?.to_string(),
+                artist: track.artist.clone(),
+                album: track.album.clone(),
+                year: track.year.unwrap_or_default(),
+            })
+        })
+        .collect::<Result<_>>()?;
+    tracks.sort_by(|a, b| {
+        (a.year, &a.artist, &a.title, &a.slug).cmp(&(b.year, &b.artist, &b.title, &b.slug))
+    });
+    Ok(tracks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::testing::TestSite;
+    use crate::track::Track;
+
+    fn track(id: &str, title: &str, album: &str, year: Option<u32>) -> Track {
+        Track {
+            id: id.to_string(),
+            title: title.to_string(),
+            artist: "Lamp".to_string(),
+            album: album.to_string(),
+            albumartist: "Lamp".to_string(),
+            year,
+            ..Track::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn generates_pages_for_every_linked_year() {
+        let mut album = Album::new("1".to_string(), "Best Of".to_string(), "Lamp".to_string());
+        album.year = Some(2010);
+        album.add_track(track("1", "Ame Furu Yoru", "Best Of", Some(2010)));
+        // Remastered onto a later album
+        album.add_track(track("2", "Yume", "Best Of", Some(2004)));
+        // Recorded the year before its album, in the same decade
+        let mut earlier = Album::new("2".to_string(), "Koibito e".to_string(), "Lamp".to_string());
+        earlier.year = Some(1999);
+        earlier.add_track(track("5", "Koibito e", "Koibito e", Some(1999)));
+        earlier.add_track(track("6", "Early Version", "Koibito e", Some(1998)));
+        let mut artist = Artist::new("Lamp".to_string());
+        artist.add_album(album);
+        artist.add_album(earlier);
+        artist.add_track(track("3", "Demo", "", Some(1999)));
+        artist.add_track(track("4", "Undated", "", None));
+        let library = Library::from([("Lamp".to_string(), artist)]);
+
+        let site = TestSite::new();
+        site.generate(&library).await;
+
+        let years = site.path("years");
+        let mut pages: Vec<String> = fs::read_dir(&years)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        pages.sort();
+        assert_eq!(
+            pages,
+            [
+                "1990s.md",
+                "1998.md",
+                "1999.md",
+                "2000s.md",
+                "2004.md",
+                "2010.md",
+                "2010s.md",
+                "_index.md"
+            ]
+        );
+
+        let year_2004 = site.read("years/2004.md");
+        assert!(
+            year_2004.contains("**0** albums, **1** tracks"),
+            "{}",
+            year_2004
+        );
+        assert!(year_2004.contains("[Yume]"));
+        let year_2010 = site.read("years/2010.md");
+        assert!(
+            year_2010.contains("**1** albums, **2** tracks"),
+            "{}",
+            year_2010
+        );
+        assert!(!year_2010.contains("## Tracks"));
+
+        let decade = site.read("years/1990s.md");
+        assert!(decade.contains("- [1999](/years/1999/)"), "{}", decade);
+        assert!(decade.contains("[Demo]"));
+        // Listed and counted once, under its album
+        assert!(decade.contains("**1** albums, **3** tracks"), "{}", decade);
+        assert!(!decade.contains("[Early Version]"), "{}", decade);
+        assert!(site.read("years/1998.md").contains("[Early Version]"));
+        let index = site.read("years/_index.md");
+        let newest = index.find("2010s").unwrap();
+        let oldest = index.find("1990s").unwrap();
+        assert!(newest < oldest, "{}", index);
+        assert!(index.contains("1 albums, 3 tracks\n"), "{}", index);
+
+        // Every year a track page links has a page
+        for entry in fs::read_dir(site.path("tracks")).unwrap() {
+            let page = fs::read_to_string(entry.unwrap().path()).unwrap();
+            if let Some(start) = page.find("](/years/") {
+                let year = &page[start + 9..start + 13];
+                assert!(years.join(format!("{}.md", year)).exists(), "{}", year);
+            }
+        }
+    }
+}
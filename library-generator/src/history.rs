@@ -0,0 +1,240 @@
+use crate::game::{PlaytimePoint, RecentPlaytime, SteamLibrary};
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Total minutes played per appid on one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub date: NaiveDate,
+    pub playtime: BTreeMap<u64, u64>,
+}
+
+impl Snapshot {
+    pub fn of(library: &SteamLibrary, date: NaiveDate) -> Self {
+        Self {
+            date,
+            playtime: library
+                .iter()
+                .map(|game| (game.appid, game.playtime_forever))
+                .collect(),
+        }
+    }
+
+    /// Save as `dir/<date>.json`, replacing an earlier snapshot of the same
+    /// day so history keeps one point per day.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", self.date));
+        let json = serde_json::to_string_pretty(self)?;
+        let mut file = File::create(path)?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Every snapshot recorded for one account, oldest first.
+#[derive(Debug, Default)]
+pub struct PlaytimeHistory {
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl PlaytimeHistory {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut snapshots = Vec::new();
+        if dir.exists() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let reader = BufReader::new(File::open(&path)?);
+                let snapshot: Snapshot = serde_json::from_reader(reader).map_err(|e| {
+                    anyhow::anyhow!("Failed to read snapshot '{}': {}", path.display(), e)
+                })?;
+                snapshots.push(snapshot);
+            }
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.date);
+        Ok(Self { snapshots })
+    }
+
+    /// Attach playtime deltas for the last week, month and year, measured
+    /// back from `today`, and each game's playtime over time. A window
+    /// reaching back past the first snapshot starts at the first snapshot.
+    pub fn apply(&self, library: &mut SteamLibrary, today: NaiveDate) {
+        if self.snapshots.is_empty() {
+            return;
+        }
+
+        let baseline = |days: u64| -> &Snapshot {
+            let since = today.checked_sub_days(Days::new(days)).unwrap_or(today);
+            self.snapshots
+                .iter()
+                .rev()
+                .find(|snapshot| snapshot.date <= since)
+                .unwrap_or(&self.snapshots[0])
+        };
+        let (week, month, year) = (baseline(7), baseline(30), baseline(365));
+
+        for game in library.iter_mut() {
+            // Games bought after a snapshot had no playtime at the time
+            let played_since = |snapshot: &Snapshot| {
+                let then = snapshot.playtime.get(&game.appid).copied().unwrap_or(0);
+                game.playtime_forever.saturating_sub(then)
+            };
+
+            // One point per change, plus today's total
+            let mut series: Vec<PlaytimePoint> = Vec::new();
+            for snapshot in &self.snapshots {
+                let Some(&minutes) = snapshot.playtime.get(&game.appid) else {
+                    continue;
+                };
+                if series.last().is_none_or(|point| point.minutes != minutes) {
+                    series.push(PlaytimePoint {
+                        date: snapshot.date,
+                        minutes,
+                    });
+                }
+            }
+            if series
+                .last()
+                .is_some_and(|point| point.date < today && point.minutes != game.playtime_forever)
+            {
+                series.push(PlaytimePoint {
+                    date: today,
+                    minutes: game.playtime_forever,
+                });
+            }
+
+            game.recent_playtime = Some(RecentPlaytime {
+                week: played_since(week),
+                month: played_since(month),
+                year: played_since(year),
+                series,
+            });
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/library-generator/steam-history` (or the platform
+/// equivalent). History isn't a cache, so it doesn't live with one.
+pub fn default_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from(".local/share"))
+        .join("library-generator")
+        .join("steam-history")
+}
+
+pub fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::generator::testing::{TestSite, steam_game as game};
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn snapshot(day: &str, playtime: &[(u64, u64)]) -> Snapshot {
+        Snapshot {
+            date: date(day),
+            playtime: playtime.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn snapshots_round_trip_one_per_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut library = vec![game(1, "Celeste", 60)];
+        Snapshot::of(&library, date("2026-10-01"))
+            .save(dir.path())
+            .unwrap();
+        library[0].playtime_forever = 90;
+        Snapshot::of(&library, date("2026-10-01"))
+            .save(dir.path())
+            .unwrap();
+        Snapshot::of(&library, date("2026-09-01"))
+            .save(dir.path())
+            .unwrap();
+
+        let history = PlaytimeHistory::load(dir.path()).unwrap();
+
+        let dates: Vec<String> = history
+            .snapshots
+            .iter()
+            .map(|s| s.date.to_string())
+            .collect();
+        assert_eq!(dates, ["2026-09-01", "2026-10-01"]);
+        assert_eq!(history.snapshots[1].playtime[&1], 90);
+    }
+
+    #[test]
+    fn computes_deltas_and_series() {
+        let history = PlaytimeHistory {
+            snapshots: vec![
+                snapshot("2025-06-01", &[(1, 600)]),
+                snapshot("2026-09-01", &[(1, 1200)]),
+                snapshot("2026-10-01", &[(1, 1200)]),
+                snapshot("2026-10-10", &[(1, 1500), (2, 0)]),
+            ],
+        };
+        let mut library = vec![game(1, "Celeste", 1560), game(2, "Hades", 120)];
+
+        history.apply(&mut library, date("2026-10-17"));
+
+        let celeste = library[0].recent_playtime.as_ref().unwrap();
+        // Latest snapshots on or before 10-10, 09-17 and 2025-10-17
+        assert_eq!((celeste.week, celeste.month, celeste.year), (60, 360, 960));
+        let series: Vec<(String, u64)> = celeste
+            .series
+            .iter()
+            .map(|p| (p.date.to_string(), p.minutes))
+            .collect();
+        assert_eq!(
+            series,
+            [
+                ("2025-06-01".to_string(), 600),
+                ("2026-09-01".to_string(), 1200),
+                ("2026-10-10".to_string(), 1500),
+                ("2026-10-17".to_string(), 1560),
+            ]
+        );
+
+        // Bought after most snapshots, so everything played is recent
+        let hades = library[1].recent_playtime.as_ref().unwrap();
+        assert_eq!((hades.week, hades.month, hades.year), (120, 120, 120));
+    }
+
+    #[tokio::test]
+    async fn generates_recently_played_page() {
+        let history = PlaytimeHistory {
+            snapshots: vec![snapshot("2026-10-01", &[(1, 600), (2, 300), (3, 50)])],
+        };
+        let mut library = vec![
+            game(1, "Celeste", 660),
+            game(2, "Hades", 420),
+            game(3, "Tunic", 50),
+        ];
+        history.apply(&mut library, date("2026-10-05"));
+
+        let site = TestSite::new();
+        site.generate_games(&library.into_iter().map(Game::from).collect())
+            .await;
+
+        let page = site.read("game-lists/recently-played.md");
+        let hades = page.find("[Hades]").unwrap();
+        let celeste = page.find("[Celeste]").unwrap();
+        assert!(hades < celeste, "{}", page);
+        assert!(page.contains("2.0h this week, 2.0h this month"));
+        assert!(!page.contains("Tunic"));
+    }
+}
@@ -1,16 +1,20 @@
 use crate::track::Track;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Album {
     pub id: String,
     pub title: String,
     pub artist: String,
-    pub year: String,
+    pub year: Option<u32>,
     pub tracktotal: u32,
     pub disctotal: u32,
     pub genre: String,
+    pub albumtype: String,
+    pub label: String,
+    pub country: String,
+    pub mb_albumid: String,
     pub tracks: Vec<Track>,
 }
 
@@ -20,18 +24,28 @@ impl Album {
             id,
             title,
             artist,
-            year: String::new(),
+            year: None,
             tracktotal: 0,
             disctotal: 1,
             genre: String::new(),
+            albumtype: String::new(),
+            label: String::new(),
+            country: String::new(),
+            mb_albumid: String::new(),
             tracks: Vec::new(),
         }
     }
 
+    /// Key identifying this album across the library; album ids alone are
+    /// not guaranteed to be set.
+    pub fn key(&self) -> String {
+        format!("{}-{}", self.id, self.title)
+    }
+
     pub fn add_track(&mut self, track: Track) {
         self.tracks.push(track);
         self.tracks
-            .sort_by_key(|t| (t.disc.parse::<u32>().unwrap_or(1), t.track_number()));
+            .sort_by_key(|t| (t.disc.unwrap_or(1), t.track.unwrap_or(0)));
     }
 
     pub fn track_count(&self) -> usize {
@@ -58,13 +72,15 @@ impl Artist {
     pub fn add_album(&mut self, album: Album) {
         self.albums.push(album);
         self.albums
-            .sort_by(|a, b| a.year.cmp(&b.year).then(a.title.cmp(&b.title)));
+            .sort_by(|a, b| (&a.year, &a.title, &a.id).cmp(&(&b.year, &b.title, &b.id)));
     }
 
     pub fn add_track(&mut self, track: Track) {
         self.tracks.push(track);
-        self.tracks.sort_by_key(|t| t.title.clone());
+        self.tracks
+            .sort_by(|a, b| (&a.title, &a.id).cmp(&(&b.title, &b.id)));
     }
 }
 
-pub type Library = HashMap<String, Artist>;
+// Ordered so generation is byte-identical for identical input
+pub type Library = BTreeMap<String, Artist>;
@@ -0,0 +1,423 @@
+use crate::game::{InstallInfo, SteamGame, SteamLibrary};
+use crate::vdf;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// StateFlags bit Steam sets once an app is fully installed
+const STATE_FULLY_INSTALLED: u64 = 4;
+
+// Runtimes and redistributables Steam installs alongside games
+const TOOL_PREFIXES: &[&str] = &[
+    "Proton",
+    "Steam Linux Runtime",
+    "Steamworks Common Redistributables",
+    "SteamVR",
+];
+
+/// An app with an `appmanifest_<appid>.acf` in one of the Steam libraries.
+#[derive(Debug, Clone)]
+pub struct InstalledApp {
+    pub appid: u64,
+    pub name: String,
+    pub install: InstallInfo,
+}
+
+/// Steam library folders listed in `steamapps/libraryfolders.vdf` under
+/// `steam_root`. The root itself is always a library, even when the file
+/// is missing.
+pub fn library_folders(steam_root: &Path) -> Result<Vec<PathBuf>> {
+    let mut folders = vec![steam_root.to_path_buf()];
+
+    let path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    if !path.exists() {
+        return Ok(folders);
+    }
+
+    let root = vdf::parse_text(&fs::read_to_string(&path)?)
+        .map_err(|e| anyhow::anyhow!("Failed to parse '{}': {}", path.display(), e))?;
+    let Some(list) = root.block("libraryfolders") else {
+        return Ok(folders);
+    };
+
+    for (key, value) in list.entries() {
+        // Entries are numbered; newer files nest the path in a block,
+        // older ones give it directly
+        if key.parse::<u32>().is_err() {
+            continue;
+        }
+        let folder = match value {
+            vdf::Value::Block(block) => block.str("path"),
+            vdf::Value::String(path) => Some(path.as_str()),
+        };
+        if let Some(folder) = folder {
+            let folder = PathBuf::from(folder);
+            if !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        }
+    }
+
+    Ok(folders)
+}
+
+/// Every fully installed app across all library folders, by appid.
+/// Manifests and directory entries that can't be read are reported and
+/// skipped.
+pub fn installed_apps(steam_root: &Path) -> Result<Vec<InstalledApp>> {
+    let mut apps = Vec::new();
+
+    for folder in library_folders(steam_root)? {
+        let steamapps = folder.join("steamapps");
+        let Ok(entries) = fs::read_dir(&steamapps) else {
+            eprintln!("Skipping missing Steam library {}", steamapps.display());
+            continue;
+        };
+
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    eprintln!("Skipping entry in {}: {}", steamapps.display(), e);
+                    continue;
+                }
+            };
+            let is_manifest = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("appmanifest_") && name.ends_with(".acf"));
+            if !is_manifest {
+                continue;
+            }
+
+            match read_manifest(&path, &folder) {
+                Ok(Some(app)) => apps.push(app),
+                Ok(None) => {}
+                Err(e) => eprintln!("Skipping {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    apps.sort_by_key(|app| app.appid);
+    apps.dedup_by_key(|app| app.appid);
+    println!("Found {} installed Steam apps", apps.len());
+    Ok(apps)
+}
+
+fn read_manifest(path: &Path, folder: &Path) -> Result<Option<InstalledApp>> {
+    let root = vdf::parse_text(&fs::read_to_string(path)?)?;
+    let state = root
+        .block("AppState")
+        .ok_or_else(|| anyhow::anyhow!("no AppState block"))?;
+
+    let appid = state
+        .number("appid")
+        .ok_or_else(|| anyhow::anyhow!("no appid"))?;
+    if state.number("StateFlags").unwrap_or(0) & STATE_FULLY_INSTALLED == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(InstalledApp {
+        appid,
+        name: state.str("name").unwrap_or_default().to_string(),
+        install: InstallInfo {
+            size_on_disk: state.number("SizeOnDisk").unwrap_or(0),
+            last_updated: state.number("LastUpdated").unwrap_or(0),
+            library: folder.to_string_lossy().into_owned(),
+        },
+    }))
+}
+
+/// Mark installed games in `library`. With `add_missing` (when nothing
+/// says which games are owned, i.e. no API fetch or local config) installed
+/// apps are added too, with no playtime; otherwise apps the library doesn't
+/// own are left out, even if it's empty. Steam's own tools never count.
+pub fn merge_installed(
+    library: &mut SteamLibrary,
+    installed: Vec<InstalledApp>,
+    add_missing: bool,
+) {
+    for app in installed {
+        if TOOL_PREFIXES
+            .iter()
+            .any(|prefix| app.name.starts_with(prefix))
+        {
+            continue;
+        }
+        match library.iter_mut().find(|game| game.appid == app.appid) {
+            Some(game) => game.installed = Some(app.install),
+            None if add_missing => library.push(SteamGame {
+                appid: app.appid,
+                name: app.name,
+                installed: Some(app.install),
+                ..SteamGame::default()
+            }),
+            None => {}
+        }
+    }
+}
+
+/// Owned games recovered from a Steam user's local config, without the Web
+/// API: `user_dir` is `userdata/<id>` under the Steam root. Playtime and last
+/// played times come from `config/localconfig.vdf`, non-Steam games from
+/// `config/shortcuts.vdf`. The config doesn't name apps, so names come from
+/// the appmanifests of installed games where possible.
+pub fn local_library(user_dir: &Path) -> Result<SteamLibrary> {
+    let config = user_dir.join("config");
+    let path = config.join("localconfig.vdf");
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    let root = vdf::parse_text(&contents)
+        .map_err(|e| anyhow::anyhow!("Failed to parse '{}': {}", path.display(), e))?;
+    let apps = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"]
+        .iter()
+        .try_fold(&root, |block, key| block.block(key))
+        .cloned()
+        .unwrap_or_default();
+
+    // userdata/<id> sits directly under the Steam root
+    let names: Vec<InstalledApp> = match user_dir.parent().and_then(Path::parent) {
+        Some(steam_root) if steam_root.join("steamapps").is_dir() => installed_apps(steam_root)?,
+        _ => Vec::new(),
+    };
+
+    let mut library = SteamLibrary::new();
+    for (key, value) in apps.entries() {
+        let (Ok(appid), vdf::Value::Block(app)) = (key.parse::<u64>(), value) else {
+            continue;
+        };
+        let game = local_game(appid, app);
+        // Apps Steam merely knows about have neither; shortcuts are named
+        // from shortcuts.vdf below
+        if (game.playtime_forever == 0 && game.rtime_last_played == 0) || game.is_shortcut() {
+            continue;
+        }
+        let name = names.iter().find(|installed| installed.appid == appid);
+        library.push(SteamGame {
+            name: match name {
+                Some(installed) => installed.name.clone(),
+                None => format!("Steam app {}", appid),
+            },
+            ..game
+        });
+    }
+    library.retain(|game| {
+        !TOOL_PREFIXES
+            .iter()
+            .any(|prefix| game.name.starts_with(prefix))
+    });
+
+    let shortcuts = config.join("shortcuts.vdf");
+    if shortcuts.exists() {
+        let root = vdf::parse_binary(&fs::read(&shortcuts)?)
+            .map_err(|e| anyhow::anyhow!("Failed to parse '{}': {}", shortcuts.display(), e))?;
+        for (_, value) in root
+            .block("shortcuts")
+            .into_iter()
+            .flat_map(vdf::Block::entries)
+        {
+            let vdf::Value::Block(shortcut) = value else {
+                continue;
+            };
+            let (Some(appid), Some(name)) = (shortcut.number("appid"), shortcut.str("AppName"))
+            else {
+                eprintln!("Skipping non-Steam shortcut without an app ID or name");
+                continue;
+            };
+            // Steam tracks shortcut playtime under the same ID, if at all
+            let game = apps
+                .block(&appid.to_string())
+                .map(|app| local_game(appid, app))
+                .unwrap_or_else(|| SteamGame {
+                    appid,
+                    ..SteamGame::default()
+                });
+            library.push(SteamGame {
+                name: name.to_string(),
+                rtime_last_played: game
+                    .rtime_last_played
+                    .max(shortcut.number("LastPlayTime").unwrap_or(0)),
+                ..game
+            });
+        }
+    }
+
+    println!("Found {} games in local Steam config", library.len());
+    Ok(library)
+}
+
+fn local_game(appid: u64, app: &vdf::Block) -> SteamGame {
+    SteamGame {
+        appid,
+        playtime_forever: app.number("Playtime").unwrap_or(0),
+        rtime_last_played: app.number("LastPlayed").unwrap_or(0),
+        ..SteamGame::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(dir: &Path, appid: u64, name: &str, flags: u32, size: u64) {
+        let steamapps = dir.join("steamapps");
+        fs::create_dir_all(&steamapps).unwrap();
+        fs::write(
+            steamapps.join(format!("appmanifest_{}.acf", appid)),
+            format!(
+                "\"AppState\"\n{{\n\t\"appid\"\t\t\"{}\"\n\t\"name\"\t\t\"{}\"\n\t\"StateFlags\"\t\t\"{}\"\n\t\"LastUpdated\"\t\t\"1700000000\"\n\t\"SizeOnDisk\"\t\t\"{}\"\n}}\n",
+                appid, name, flags, size
+            ),
+        )
+        .unwrap();
+    }
+
+    // A Steam root with a second library on another drive
+    fn steam_root() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        let games = root.path().join("games");
+        fs::create_dir_all(root.path().join("steamapps")).unwrap();
+        fs::write(
+            root.path().join("steamapps/libraryfolders.vdf"),
+            format!(
+                "\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n\t\"1\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t\t\"apps\"\n\t\t{{\n\t\t\t\"413150\"\t\t\"1\"\n\t\t}}\n\t}}\n}}\n",
+                root.path().display(),
+                games.display()
+            ),
+        )
+        .unwrap();
+
+        manifest(root.path(), 620, "Portal 2", 4, 12_000_000_000);
+        manifest(
+            root.path(),
+            1493710,
+            "Proton Experimental",
+            4,
+            1_000_000_000,
+        );
+        manifest(&games, 413150, "Stardew Valley", 1030, 600_000_000);
+        // Still downloading
+        manifest(&games, 1145360, "Hades", 1026 & !4, 0);
+        root
+    }
+
+    #[test]
+    fn finds_installed_apps_across_libraries() {
+        let root = steam_root();
+
+        let folders = library_folders(root.path()).unwrap();
+        assert_eq!(folders.len(), 2);
+
+        let apps = installed_apps(root.path()).unwrap();
+        let ids: Vec<u64> = apps.iter().map(|app| app.appid).collect();
+        assert_eq!(ids, [620, 413150, 1493710]);
+        assert_eq!(apps[1].install.size_display(), "600 MB");
+        assert_eq!(apps[0].install.size_display(), "12.0 GB");
+        assert!(apps[1].install.library.ends_with("games"));
+    }
+
+    #[test]
+    fn marks_owned_games_or_adds_installed_ones() {
+        let root = steam_root();
+
+        let mut owned = vec![SteamGame {
+            appid: 413150,
+            name: "Stardew Valley".to_string(),
+            playtime_forever: 5400,
+            ..SteamGame::default()
+        }];
+        merge_installed(&mut owned, installed_apps(root.path()).unwrap(), false);
+        assert_eq!(owned.len(), 1);
+        assert_eq!(
+            owned[0].installed.as_ref().unwrap().last_updated,
+            1700000000
+        );
+
+        // A private profile or new account really does own nothing
+        let mut none_owned = SteamLibrary::new();
+        merge_installed(&mut none_owned, installed_apps(root.path()).unwrap(), false);
+        assert!(none_owned.is_empty());
+
+        let mut local = SteamLibrary::new();
+        merge_installed(&mut local, installed_apps(root.path()).unwrap(), true);
+        let names: Vec<&str> = local.iter().map(|game| game.name.as_str()).collect();
+        assert_eq!(names, ["Portal 2", "Stardew Valley"]);
+    }
+
+    #[test]
+    fn reads_playtime_from_local_config() {
+        let root = steam_root();
+        let config = root.path().join("userdata/123/config");
+        fs::create_dir_all(&config).unwrap();
+        fs::write(
+            config.join("localconfig.vdf"),
+            r#"
+"UserLocalConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"413150"
+					{
+						"LastPlayed"		"1700000000"
+						"Playtime"		"5400"
+					}
+					"440"
+					{
+						"Playtime"		"90"
+					}
+					"1493710"
+					{
+						"LastPlayed"		"1690000000"
+					}
+					"570"
+					{
+						"cloud"		{ }
+					}
+					"3000000000"
+					{
+						"Playtime"		"30"
+					}
+				}
+			}
+		}
+	}
+}
+"#,
+        )
+        .unwrap();
+
+        let mut shortcuts = b"\x00shortcuts\x00\x000\x00\x02appid\x00".to_vec();
+        shortcuts.extend(3_000_000_000u32.to_le_bytes());
+        shortcuts.extend(b"\x01AppName\x00Doom\x00\x02LastPlayTime\x00");
+        shortcuts.extend(1_710_000_000u32.to_le_bytes());
+        shortcuts.extend(b"\x08\x08\x08");
+        fs::write(config.join("shortcuts.vdf"), shortcuts).unwrap();
+
+        let library = local_library(&root.path().join("userdata/123")).unwrap();
+        let games: Vec<(u64, &str, u64, u64)> = library
+            .iter()
+            .map(|game| {
+                (
+                    game.appid,
+                    game.name.as_str(),
+                    game.playtime_forever,
+                    game.rtime_last_played,
+                )
+            })
+            .collect();
+        assert_eq!(
+            games,
+            [
+                (413150, "Stardew Valley", 5400, 1700000000),
+                (440, "Steam app 440", 90, 0),
+                (3000000000, "Doom", 30, 1710000000),
+            ]
+        );
+    }
+}
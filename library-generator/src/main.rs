@@ -1,13 +1,32 @@
+mod beets;
+#[cfg(test)]
+mod fake_steam;
 mod game;
+mod game_sources;
 mod generator;
+mod history;
 mod library;
+mod local_steam;
+mod manifest;
+mod overlay;
 mod parser;
+mod scanner;
+mod slugs;
+mod soundtracks;
 mod steam;
+mod taxonomy;
 mod track;
+mod vdf;
 
 use anyhow::Result;
 use clap::Parser;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Cache ages past ten years are as good as never refetching, and bounding
+// them keeps the durations built from them from overflowing
+const MAX_CACHE_AGE_DAYS: i64 = 10 * 365;
+const MAX_CACHE_AGE_HOURS: i64 = MAX_CACHE_AGE_DAYS * 24;
 
 #[derive(Parser)]
 #[command(name = "library-generator")]
@@ -17,17 +36,152 @@ struct Cli {
     #[arg(short = 'm', long)]
     music_input: Option<PathBuf>,
 
-    /// Fetch Steam library (requires STEAM_API_KEY and STEAM_ID env vars)
+    /// Path to a beets library.db to read instead of a JSON export
+    #[arg(long, conflicts_with = "music_input")]
+    beets_db: Option<PathBuf>,
+
+    /// Music directory to scan for tagged audio files instead of an export
+    #[arg(long, conflicts_with_all = ["music_input", "beets_db"])]
+    music_dir: Option<PathBuf>,
+
+    /// Fetch Steam library (requires STEAM_ID and an API key, from
+    /// STEAM_API_KEY or --steam-key-file)
     #[arg(short = 's', long)]
     steam: bool,
 
+    /// Steam install directory to read installed games from, no API key
+    /// needed (e.g. ~/.local/share/Steam)
+    #[arg(long)]
+    steam_root: Option<PathBuf>,
+
+    /// Steam user directory to read playtime from instead of the Web API,
+    /// no API key needed (e.g. ~/.local/share/Steam/userdata/<id>)
+    #[arg(long, conflicts_with = "steam")]
+    steam_local: Option<PathBuf>,
+
+    /// GOG Galaxy 2.0 database to read games from (galaxy-2.0.db)
+    #[arg(long)]
+    gog_galaxy_db: Option<PathBuf>,
+
+    /// Heroic Games Launcher config directory to read games from
+    /// (e.g. ~/.config/heroic)
+    #[arg(long)]
+    heroic_config: Option<PathBuf>,
+
+    /// Lutris database to read games from (e.g. ~/.local/share/lutris/pga.db)
+    #[arg(long)]
+    lutris_db: Option<PathBuf>,
+
+    /// TOML or YAML file of extra games (console, physical, ...) and backlog
+    /// status, rating and notes for games from any store
+    #[arg(long)]
+    games_overlay: Option<PathBuf>,
+
     /// Clear Steam cache before fetching
     #[arg(long)]
     clear_steam_cache: bool,
 
+    /// Also fetch per-game achievements (one API request per game)
+    #[arg(long, requires = "steam")]
+    steam_achievements: bool,
+
+    /// Also fetch store page details: genres, developers, release dates
+    #[arg(long, requires = "steam")]
+    steam_store: bool,
+
+    /// Refetch cached store details older than this many days
+    #[arg(
+        long,
+        default_value_t = 30,
+        value_parser = clap::value_parser!(i64).range(0..=MAX_CACHE_AGE_DAYS)
+    )]
+    steam_store_max_age: i64,
+
+    /// Base URL of the Steam store
+    #[arg(long, default_value = steam::DEFAULT_STORE_URL)]
+    steam_store_url: String,
+
+    /// Also download game icons and header images into the static directory
+    #[arg(long, requires = "steam")]
+    steam_artwork: bool,
+
+    /// Download artwork from this host instead of Steam's media servers
+    #[arg(long)]
+    steam_media_url: Option<String>,
+
+    /// Read the Steam API key from this file instead of STEAM_API_KEY
+    #[arg(long)]
+    steam_key_file: Option<PathBuf>,
+
+    /// Directory for cached Steam API responses [default: XDG cache dir]
+    #[arg(long)]
+    steam_cache_dir: Option<PathBuf>,
+
+    /// Directory for Steam playtime snapshots [default: XDG data dir]
+    #[arg(long)]
+    steam_history_dir: Option<PathBuf>,
+
+    /// Refetch cached Steam data older than this many hours
+    #[arg(
+        long,
+        default_value_t = 24,
+        value_parser = clap::value_parser!(i64).range(0..=MAX_CACHE_AGE_HOURS)
+    )]
+    steam_max_age: i64,
+
+    /// Only use cached Steam data, failing if there is none
+    #[arg(long, conflicts_with = "refresh")]
+    offline: bool,
+
+    /// Ignore cached Steam data and fetch everything again
+    #[arg(long)]
+    refresh: bool,
+
+    /// Seconds to wait for a Steam API response
+    #[arg(long, default_value_t = 30)]
+    steam_timeout: u64,
+
+    /// Times to retry a rate-limited or failed Steam API request
+    #[arg(long, default_value_t = 3)]
+    steam_retries: u32,
+
+    /// Base URL of the Steam Web API
+    #[arg(long, default_value = steam::DEFAULT_API_URL)]
+    steam_api_url: String,
+
+    /// TOML file mapping album ids to Steam app IDs, for soundtracks the
+    /// title match misses or gets wrong
+    #[arg(long)]
+    soundtrack_overrides: Option<PathBuf>,
+
+    /// Games played for less than this many hours count as barely played
+    #[arg(long, default_value_t = 1.0)]
+    barely_played_hours: f64,
+
+    /// Games not played for this many years count as untouched
+    #[arg(long, default_value_t = 2)]
+    untouched_years: u32,
+
     /// Output directory for generated content
     #[arg(short, long, default_value = "content")]
     output: PathBuf,
+
+    /// Static directory for downloaded images
+    #[arg(long, default_value = "static")]
+    static_dir: PathBuf,
+
+    /// Manifest of files generated by the previous run [default:
+    /// generated-manifest.json next to the output directory]
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+
+    /// Delete previously generated files that are no longer produced
+    #[arg(long)]
+    prune: bool,
+
+    /// List the files --prune would delete without deleting them
+    #[arg(long, requires = "prune")]
+    dry_run: bool,
 }
 
 #[tokio::main]
@@ -37,31 +191,202 @@ async fn main() -> Result<()> {
     println!("Library Generator");
 
     // Initialize generator
-    let generator = generator::Generator::new(cli.output.to_str().unwrap().to_string())?;
+    let mut generator = generator::Generator::new(
+        cli.output.to_str().unwrap().to_string(),
+        cli.manifest.clone().unwrap_or_else(|| {
+            cli.output
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(manifest::DEFAULT_FILE)
+        }),
+    )?
+    .with_backlog_thresholds(generator::BacklogThresholds {
+        barely_played_minutes: (cli.barely_played_hours * 60.0).round().max(0.0) as u64,
+        untouched_years: cli.untouched_years,
+    });
 
-    // Generate music library if input provided
-    if let Some(music_path) = cli.music_input {
+    // Load the music library from whichever input was given
+    let mut parser = parser::Parser::new();
+    let mut music = None;
+    if let Some(music_path) = &cli.music_input {
         println!("Music Input: {:?}", music_path);
-        let mut parser = parser::Parser::new();
-        let library = parser.parse_file(music_path.to_str().unwrap()).await?;
-        generator.generate(&library).await?;
+        music = Some(parser.parse_file(music_path.to_str().unwrap()).await?);
+    }
+    if let Some(db_path) = &cli.beets_db {
+        println!("Beets Database: {:?}", db_path);
+        music = Some(parser.parse_beets_db(db_path.to_str().unwrap()).await?);
+    }
+    if let Some(music_dir) = &cli.music_dir {
+        music = Some(parser.parse_directory(music_dir).await?);
     }
 
-    // Generate Steam library if requested
-    if cli.steam {
-        if cli.clear_steam_cache {
-            steam::SteamClient::clear_cache()?;
-        }
+    // Load games from every store given
+    let games = if cli.steam
+        || cli.steam_local.is_some()
+        || cli.steam_root.is_some()
+        || cli.gog_galaxy_db.is_some()
+        || cli.heroic_config.is_some()
+        || cli.lutris_db.is_some()
+        || cli.games_overlay.is_some()
+    {
+        Some(load_games(&cli).await?)
+    } else {
+        None
+    };
 
-        let api_key =
-            std::env::var("STEAM_API_KEY").expect("STEAM_API_KEY environment variable not set");
-        let steam_id = std::env::var("STEAM_ID").expect("STEAM_ID environment variable not set");
+    // Cross-link soundtracks when both halves of the site are generated
+    if let (Some(music), Some(games)) = (&music, &games) {
+        let overrides = match &cli.soundtrack_overrides {
+            Some(path) => soundtracks::Overrides::load(path)?,
+            None => soundtracks::Overrides::default(),
+        };
+        generator =
+            generator.with_soundtracks(soundtracks::Soundtracks::link(music, games, &overrides)?);
+    }
 
-        let client = steam::SteamClient::new(api_key, steam_id);
-        let games = client.fetch_library().await?;
-        generator.generate_games(&games).await?;
+    if let Some(music) = &music {
+        generator.generate(music).await?;
     }
+    if let Some(games) = &games {
+        generator.generate_games(games).await?;
+    }
+
+    generator.finish(cli.prune, cli.dry_run).await?;
 
     println!("Done!");
     Ok(())
 }
+
+/// Games from every store given on the command line, merged by title, with
+/// the games overlay on top. Steam comes first, so its names win.
+async fn load_games(cli: &Cli) -> Result<game::GameLibrary> {
+    let overlay = match &cli.games_overlay {
+        Some(path) => overlay::Overlay::load(path)?,
+        None => overlay::Overlay::default(),
+    };
+
+    let mut libraries = Vec::new();
+    if cli.steam || cli.steam_local.is_some() || cli.steam_root.is_some() {
+        let steam = load_steam_games(cli).await?;
+        libraries.push(steam.into_iter().map(game::Game::from).collect());
+    }
+    if let Some(path) = &cli.gog_galaxy_db {
+        libraries.push(game_sources::gog_galaxy_library(path)?);
+    }
+    if let Some(config_dir) = &cli.heroic_config {
+        libraries.push(game_sources::heroic_library(config_dir)?);
+    }
+    if let Some(path) = &cli.lutris_db {
+        libraries.push(game_sources::lutris_library(path)?);
+    }
+
+    let mut games = game_sources::merge_libraries(libraries);
+    overlay.apply(&mut games);
+    Ok(games)
+}
+
+/// The Steam library from the API or local config, with installed games and
+/// any requested extras filled in.
+async fn load_steam_games(cli: &Cli) -> Result<game::SteamLibrary> {
+    let client = if cli.steam {
+        Some(steam_client(cli)?)
+    } else {
+        None
+    };
+
+    let mut games = game::SteamLibrary::new();
+    if let Some(client) = &client {
+        games = client.fetch_library().await?;
+        client
+            .playtime_history()?
+            .apply(&mut games, history::today());
+    }
+    if let Some(user_dir) = &cli.steam_local {
+        games = local_steam::local_library(user_dir)?;
+    }
+    if let Some(steam_root) = &cli.steam_root {
+        let installed = local_steam::installed_apps(steam_root)?;
+        // Without a list of owned games, installed ones are all we know of
+        let add_missing = client.is_none() && cli.steam_local.is_none();
+        local_steam::merge_installed(&mut games, installed, add_missing);
+    }
+    if let Some(client) = &client {
+        if cli.steam_achievements {
+            client.fetch_achievements(&mut games).await?;
+        }
+        if cli.steam_store {
+            client.fetch_store_details(&mut games).await?;
+        }
+        if cli.steam_artwork {
+            client.download_artwork(&mut games, &cli.static_dir).await?;
+        }
+    }
+
+    Ok(games)
+}
+
+/// A Steam API client configured from the command line and environment.
+fn steam_client(cli: &Cli) -> Result<steam::SteamClient> {
+    let steam_id = std::env::var("STEAM_ID")
+        .map_err(|_| anyhow::anyhow!("STEAM_ID environment variable not set"))?;
+    // The key is only needed to talk to the API
+    let api_key = match steam_api_key(cli.steam_key_file.as_deref()) {
+        Ok(key) => key,
+        Err(_) if cli.offline => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let cache_mode = if cli.offline {
+        steam::CacheMode::Offline
+    } else if cli.refresh {
+        steam::CacheMode::Refresh
+    } else {
+        steam::CacheMode::Normal
+    };
+
+    let mut client = steam::SteamClient::new(api_key, steam_id)
+        .with_api_url(cli.steam_api_url.clone())
+        .with_cache_mode(cache_mode)
+        .with_store_url(cli.steam_store_url.clone())
+        .with_max_age(chrono::Duration::hours(cli.steam_max_age))
+        .with_store_max_age(chrono::Duration::days(cli.steam_store_max_age))
+        .with_timeout(Duration::from_secs(cli.steam_timeout))
+        .with_retries(cli.steam_retries, Duration::from_secs(1));
+    if let Some(media_url) = &cli.steam_media_url {
+        client = client.with_media_url(media_url.clone());
+    }
+    if let Some(cache_dir) = &cli.steam_cache_dir {
+        client = client.with_cache_dir(cache_dir.clone());
+    }
+    if let Some(history_dir) = &cli.steam_history_dir {
+        client = client.with_history_dir(history_dir.clone());
+    }
+
+    if cli.clear_steam_cache {
+        client.clear_cache()?;
+    }
+
+    Ok(client)
+}
+
+/// The Steam API key from `key_file` if given, else from STEAM_API_KEY.
+fn steam_api_key(key_file: Option<&Path>) -> Result<String> {
+    let key = match key_file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to read Steam API key file '{}': {}",
+                path.display(),
+                e
+            )
+        })?,
+        None => std::env::var("STEAM_API_KEY").map_err(|_| {
+            anyhow::anyhow!("STEAM_API_KEY environment variable not set (or pass --steam-key-file)")
+        })?,
+    };
+
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(anyhow::anyhow!("Steam API key is empty"));
+    }
+    Ok(key.to_string())
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+// Kept beside the output directory, not in it, so the site never publishes it
+pub const DEFAULT_FILE: &str = "generated-manifest.json";
+
+// Manifest scopes, one per generator entry point
+pub const MUSIC: &str = "music";
+pub const GAMES: &str = "games";
+
+/// Files produced by a generator run, relative to the output directory.
+///
+/// Only files listed in a previous manifest are ever pruned, so anything
+/// written by hand next to the generated content is left alone.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub generated_at: String,
+    pub files: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(|e| anyhow::anyhow!("Failed to read manifest '{}': {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        let mut file = File::create(path)?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, scope: &str, file: String) {
+        self.files
+            .entry(scope.to_string())
+            .or_default()
+            .insert(file);
+    }
+
+    pub fn contains(&self, file: &str) -> bool {
+        self.files.values().any(|files| files.contains(file))
+    }
+
+    pub fn scope_of(&self, file: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|(_, files)| files.contains(file))
+            .map(|(scope, _)| scope.as_str())
+    }
+
+    /// Files the previous manifest had in a scope this run regenerated but
+    /// that weren't produced again.
+    pub fn stale_since(&self, previous: &Manifest) -> Vec<String> {
+        let mut stale = Vec::new();
+        for (scope, files) in &self.files {
+            if let Some(old) = previous.files.get(scope) {
+                stale.extend(old.difference(files).cloned());
+            }
+        }
+        stale
+    }
+}
@@ -0,0 +1,241 @@
+//! A hand-maintained file of games no launcher knows about (console and
+//! physical games) and of backlog details for games that come from one.
+
+use crate::game::{Game, GameLibrary, Ownership, Status, title_words};
+use anyhow::Result;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// Store name for manual games that don't say what they're played on
+const OTHER_PLATFORM: &str = "Other";
+
+/// The overlay file, in TOML or YAML depending on its extension:
+///
+/// ```toml
+/// [[games]]
+/// name = "The Legend of Zelda: Tears of the Kingdom"
+/// platform = "Nintendo Switch"
+/// status = "beaten"
+/// rating = 9
+/// hours = 120
+///
+/// # Backlog details for a Steam game
+/// [[games]]
+/// appid = 413150
+/// status = "playing"
+/// notes = "Year 3, still no greenhouse"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Overlay {
+    #[serde(default)]
+    games: Vec<OverlayGame>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OverlayGame {
+    name: Option<String>,
+    appid: Option<u64>,
+    platform: Option<String>,
+    status: Option<Status>,
+    rating: Option<u8>,
+    #[serde(default)]
+    notes: String,
+    hours: Option<f64>,
+}
+
+impl Overlay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!("Failed to read games overlay '{}': {}", path.display(), e)
+        })?;
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+        let overlay: Self = if is_yaml {
+            serde_yaml_ng::from_str(&contents).map_err(|e| anyhow::anyhow!("{}", e))
+        } else {
+            toml::from_str(&contents).map_err(|e| anyhow::anyhow!("{}", e))
+        }
+        .map_err(|e| {
+            anyhow::anyhow!("Failed to parse games overlay '{}': {}", path.display(), e)
+        })?;
+
+        for (index, entry) in overlay.games.iter().enumerate() {
+            let entry_name = || {
+                entry
+                    .name
+                    .clone()
+                    .or(entry.appid.map(|appid| format!("app {}", appid)))
+                    .unwrap_or_else(|| format!("entry {}", index + 1))
+            };
+            if entry.name.is_none() && entry.appid.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Games overlay entry {} needs a name or an appid",
+                    index + 1
+                ));
+            }
+            if entry.rating.is_some_and(|rating| rating > 10) {
+                return Err(anyhow::anyhow!(
+                    "Games overlay rating for {} must be out of 10",
+                    entry_name()
+                ));
+            }
+            if entry.appid.is_some() && (entry.platform.is_some() || entry.hours.is_some()) {
+                eprintln!(
+                    "Ignoring platform and hours for Steam {}; Steam provides those",
+                    entry_name()
+                );
+            }
+        }
+
+        Ok(overlay)
+    }
+
+    /// Merge the overlay into `library`. Entries are matched to games by
+    /// Steam app ID, or by title like store copies are; unmatched named
+    /// entries become games owned on their platform. Status, rating and
+    /// notes the entry leaves out are kept.
+    pub fn apply(&self, library: &mut GameLibrary) {
+        for entry in &self.games {
+            let existing = match (entry.appid, &entry.name) {
+                (Some(appid), _) => {
+                    let game = library.iter().position(|game| game.appid() == Some(appid));
+                    if game.is_none() {
+                        eprintln!("Games overlay names unknown Steam app {}", appid);
+                        continue;
+                    }
+                    game
+                }
+                (None, Some(name)) => {
+                    let words = title_words(name);
+                    library
+                        .iter()
+                        .position(|game| title_words(&game.name) == words)
+                }
+                (None, None) => continue,
+            };
+
+            let game = match existing {
+                Some(index) => &mut library[index],
+                None => {
+                    library.push(Game {
+                        name: entry.name.clone().unwrap_or_default(),
+                        ..Game::default()
+                    });
+                    library.last_mut().unwrap()
+                }
+            };
+
+            // Playing a game on another platform too, or a game of its own
+            if entry.appid.is_none() && (existing.is_none() || entry.platform.is_some()) {
+                game.add_ownership(Ownership {
+                    store: entry
+                        .platform
+                        .clone()
+                        .unwrap_or_else(|| OTHER_PLATFORM.to_string()),
+                    id: game.name.clone(),
+                    playtime: (entry.hours.unwrap_or(0.0) * 60.0).round().max(0.0) as u64,
+                    last_played: 0,
+                });
+            }
+            game.status = entry.status.or(game.status);
+            game.rating = entry.rating.or(game.rating);
+            if !entry.notes.is_empty() {
+                game.notes = entry.notes.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::SteamGame;
+
+    fn load(file: &str, contents: &str) -> Result<Overlay> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file);
+        fs::write(&path, contents).unwrap();
+        Overlay::load(&path)
+    }
+
+    #[test]
+    fn adds_manual_games_and_overrides_steam_ones() {
+        let overlay = load(
+            "games.toml",
+            r#"
+[[games]]
+name = "Tears of the Kingdom"
+platform = "Nintendo Switch"
+status = "beaten"
+rating = 9
+hours = 120.5
+
+[[games]]
+appid = 413150
+status = "playing"
+notes = "Year 3"
+
+[[games]]
+name = "HADES"
+status = "abandoned"
+"#,
+        )
+        .unwrap();
+
+        let mut library = vec![
+            Game {
+                rating: Some(7),
+                ..Game::from(SteamGame {
+                    appid: 413150,
+                    name: "Stardew Valley".to_string(),
+                    ..SteamGame::default()
+                })
+            },
+            Game::new(
+                "Hades".to_string(),
+                Ownership {
+                    store: "GOG".to_string(),
+                    id: "1".to_string(),
+                    ..Ownership::default()
+                },
+            ),
+        ];
+        overlay.apply(&mut library);
+
+        assert_eq!(library[0].status, Some(Status::Playing));
+        assert_eq!(library[0].rating, Some(7));
+        assert_eq!(library[0].notes, "Year 3");
+
+        // Matched by title without a platform, so no new ownership
+        assert_eq!(library[1].status, Some(Status::Abandoned));
+        assert_eq!(library[1].owned.len(), 1);
+
+        let zelda = &library[2];
+        assert_eq!(zelda.name, "Tears of the Kingdom");
+        assert_eq!(zelda.owned[0].store, "Nintendo Switch");
+        assert_eq!(zelda.playtime(), 7230);
+        assert_eq!(zelda.status, Some(Status::Beaten));
+        assert_eq!(zelda.rating, Some(9));
+    }
+
+    #[test]
+    fn reads_yaml_and_rejects_bad_entries() {
+        let overlay = load(
+            "games.yaml",
+            "games:\n  - name: Outer Wilds\n    platform: PS5\n    status: backlog\n",
+        )
+        .unwrap();
+        let mut library = GameLibrary::new();
+        overlay.apply(&mut library);
+        assert_eq!(library[0].owned[0].store, "PS5");
+        assert_eq!(library[0].status, Some(Status::Backlog));
+
+        assert!(load("games.toml", "[[games]]\nstatus = \"beaten\"\n").is_err());
+        assert!(load("games.toml", "[[games]]\nname = \"x\"\nrating = 11\n").is_err());
+        assert!(load("games.toml", "[[games]]\nname = \"x\"\nstatus = \"done\"\n").is_err());
+        assert!(load("games.toml", "[[games]]\nname = \"x\"\nscore = 3\n").is_err());
+    }
+}
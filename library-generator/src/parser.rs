@@ -1,20 +1,23 @@
+use crate::beets::BeetsDb;
 use crate::library::{Album, Artist, Library};
-use crate::track::Track;
+use crate::scanner;
+use crate::track::{self, Track, TrackReport};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
+use std::path::Path;
 
 pub struct Parser {
-    artists: HashMap<String, Artist>,
-    albums: HashMap<String, Album>,
+    artists: BTreeMap<String, Artist>,
+    albums: BTreeMap<String, Album>,
 }
 
 impl Parser {
     pub fn new() -> Self {
         Self {
-            artists: HashMap::new(),
-            albums: HashMap::new(),
+            artists: BTreeMap::new(),
+            albums: BTreeMap::new(),
         }
     }
 
@@ -22,7 +25,55 @@ impl Parser {
         let file = File::open(file_path)?;
         let reader = BufReader::new(file);
 
-        let tracks: Vec<Track> = serde_json::from_reader(reader)?;
+        let records: Vec<serde_json::Value> = serde_json::from_reader(reader)?;
+
+        println!("Parsing {} tracks...", records.len());
+
+        let mut report = TrackReport::default();
+        let tracks = track::from_json_records(records, &mut report);
+        report.print();
+
+        for track in tracks {
+            if track.is_empty() {
+                continue;
+            }
+
+            self.process_track(track);
+        }
+
+        Ok(self.artists.clone())
+    }
+
+    pub async fn parse_beets_db(&mut self, db_path: &str) -> Result<Library> {
+        let db = BeetsDb::open(db_path)?;
+
+        // Seed albums from the albums table so album-level fields come from
+        // there rather than from whichever track is seen first
+        for album in db.albums() {
+            self.albums.insert(album.key(), album.clone());
+        }
+
+        let tracks = db.tracks()?;
+
+        println!("Parsing {} tracks...", tracks.len());
+
+        for track in tracks {
+            if track.is_empty() {
+                continue;
+            }
+
+            self.process_track(track);
+        }
+
+        Ok(self.artists.clone())
+    }
+
+    pub async fn parse_directory(&mut self, dir: &Path) -> Result<Library> {
+        println!("Scanning {}...", dir.display());
+
+        let mut report = TrackReport::default();
+        let tracks = scanner::scan(dir, &mut report)?;
+        report.print();
 
         println!("Parsing {} tracks...", tracks.len());
 
@@ -61,14 +112,25 @@ impl Parser {
                     track.album.clone(),
                     artist_name.clone(),
                 );
-                album.year = track.year.clone();
+                album.year = track.year;
                 album.genre = track.genre.clone();
-                if let Ok(total) = track.tracktotal.parse::<u32>() {
-                    album.tracktotal = total;
-                }
+                album.albumtype = track.albumtype.clone();
+                album.mb_albumid = track.mb_albumid.clone();
+                album.label = track.label.clone();
+                album.country = track.country.clone();
                 album
             });
 
+            if album.year.is_none() {
+                album.year = track.year;
+            }
+
+            if album.tracktotal == 0
+                && let Some(total) = track.tracktotal
+            {
+                album.tracktotal = total;
+            }
+
             album.add_track(track.clone());
 
             // Always update the artist's album reference to get latest track list
@@ -0,0 +1,301 @@
+use crate::track::{RawTrack, Track, TrackReport};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::codecs::{
+    CODEC_TYPE_AAC, CODEC_TYPE_ALAC, CODEC_TYPE_FLAC, CODEC_TYPE_MP3, CODEC_TYPE_OPUS,
+    CODEC_TYPE_VORBIS, CodecParameters,
+};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use walkdir::WalkDir;
+
+// File extensions worth probing; everything else (covers, cue sheets, logs) is skipped
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "ogg", "oga", "opus", "m4a", "mp4"];
+
+/// Walk `dir` and read every audio file's embedded tags into a `Track`.
+///
+/// Files and directories that can't be read are reported and skipped rather
+/// than failing the whole scan; tag values that don't parse are recorded in
+/// `report`.
+pub fn scan(dir: &Path, report: &mut TrackReport) -> Result<Vec<Track>> {
+    let mut tracks = Vec::new();
+
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("Skipping {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() || !is_audio(entry.path()) {
+            continue;
+        }
+
+        match read_track(entry.path()) {
+            Ok(raw) => tracks.push(raw.into_track(tracks.len(), report)),
+            Err(e) => eprintln!("Skipping {}: {}", entry.path().display(), e),
+        }
+    }
+
+    Ok(tracks)
+}
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+fn read_track(path: &Path) -> Result<RawTrack> {
+    let file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut probed = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    // ID3v2 tags are read during probing; Vorbis comments and MP4 atoms
+    // belong to the container and are read by the format reader
+    let mut tags: Vec<Tag> = Vec::new();
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        tags.extend(revision.tags().iter().cloned());
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        tags.extend(revision.tags().iter().cloned());
+    }
+
+    let (track_id, params) = probed
+        .format
+        .default_track()
+        .map(|t| (t.id, t.codec_params.clone()))
+        .ok_or_else(|| anyhow::anyhow!("no audio track"))?;
+
+    // Bitrate is the audio stream's own: the file size would also count tags
+    // and embedded cover art. Packets are read without being decoded.
+    let mut audio_bytes = 0u64;
+    let mut end = 0u64;
+    while let Ok(packet) = probed.format.next_packet() {
+        if packet.track_id() == track_id {
+            audio_bytes += packet.data.len() as u64;
+            end = end.max(packet.ts() + packet.dur());
+        }
+    }
+
+    let path_str = path.to_string_lossy().into_owned();
+    let seconds = match duration_secs(&params) {
+        0.0 => params
+            .time_base
+            .map(|time_base| {
+                let time = time_base.calc_time(end);
+                time.seconds as f64 + time.frac
+            })
+            .unwrap_or(0.0),
+        seconds => seconds,
+    };
+
+    let mut track = RawTrack {
+        id: path_str.clone().into(),
+        format: format_name(&params).to_string().into(),
+        length: seconds.to_string().into(),
+        // Average over the stream, so VBR files get their mean bitrate
+        bitrate: if seconds > 0.0 && audio_bytes > 0 {
+            format!(
+                "{}kbps",
+                (audio_bytes as f64 * 8.0 / seconds / 1000.0).round()
+            )
+        } else {
+            String::new()
+        }
+        .into(),
+        path: path_str.into(),
+        // UTC, so scans come out the same whatever the host's timezone
+        added: metadata
+            .modified()
+            .ok()
+            .map(|time| {
+                DateTime::<Utc>::from(time)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default()
+            .into(),
+        ..RawTrack::default()
+    };
+
+    for tag in &tags {
+        let Some(key) = tag.std_key else { continue };
+        let value = tag.value.to_string().trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+
+        match key {
+            StandardTagKey::TrackTitle => track.title = value.into(),
+            StandardTagKey::Artist => track.artist = value.into(),
+            StandardTagKey::Album => track.album = value.into(),
+            StandardTagKey::AlbumArtist => track.albumartist = value.into(),
+            StandardTagKey::Date | StandardTagKey::ReleaseDate if track.year.is_empty() => {
+                track.year = value.into();
+            }
+            StandardTagKey::Genre => track.genre = value.into(),
+            StandardTagKey::TrackNumber => track.track = value.into(),
+            StandardTagKey::TrackTotal => track.tracktotal = value.into(),
+            StandardTagKey::DiscNumber => track.disc = value.into(),
+            StandardTagKey::DiscTotal => track.disctotal = value.into(),
+            StandardTagKey::Comment => track.comments = value.into(),
+            StandardTagKey::Bpm => track.bpm = value.into(),
+            StandardTagKey::Composer => track.composer = value.into(),
+            StandardTagKey::Label => track.label = value.into(),
+            StandardTagKey::ReleaseCountry => track.country = value.into(),
+            StandardTagKey::MusicBrainzReleaseType => track.albumtype = value.into(),
+            StandardTagKey::MusicBrainzTrackId => track.mb_trackid = value.into(),
+            StandardTagKey::MusicBrainzAlbumId => track.mb_albumid = value.into(),
+            StandardTagKey::MusicBrainzArtistId => track.mb_artistid = value.into(),
+            _ => {}
+        }
+    }
+
+    // Files carry no album id, so group by release id or album artist + title
+    if !track.album.is_empty() {
+        track.album_id = if !track.mb_albumid.is_empty() {
+            track.mb_albumid.clone()
+        } else if !track.albumartist.is_empty() {
+            format!("{}/{}", *track.albumartist, *track.album).into()
+        } else {
+            format!("{}/{}", *track.artist, *track.album).into()
+        };
+    }
+
+    Ok(track)
+}
+
+fn duration_secs(params: &CodecParameters) -> f64 {
+    match (params.time_base, params.n_frames) {
+        (Some(time_base), Some(frames)) => {
+            let time = time_base.calc_time(frames);
+            time.seconds as f64 + time.frac
+        }
+        _ => match (params.sample_rate, params.n_frames) {
+            (Some(rate), Some(frames)) if rate > 0 => frames as f64 / rate as f64,
+            _ => 0.0,
+        },
+    }
+}
+
+// Format names as beets reports them
+fn format_name(params: &CodecParameters) -> &'static str {
+    match params.codec {
+        CODEC_TYPE_MP3 => "MP3",
+        CODEC_TYPE_FLAC => "FLAC",
+        CODEC_TYPE_AAC => "AAC",
+        CODEC_TYPE_ALAC => "ALAC",
+        CODEC_TYPE_VORBIS => "OGG",
+        CODEC_TYPE_OPUS => "Opus",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // One silent MPEG-1 Layer III frame: 128kbps, 44.1kHz, mono
+    fn mp3_frame() -> Vec<u8> {
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0xC0];
+        frame.resize(417, 0);
+        frame
+    }
+
+    // ID3v2.3 tag with text frames and a picture of `cover` bytes
+    fn id3_tag(frames: &[(&str, &str)], cover: usize) -> Vec<u8> {
+        let mut body = Vec::new();
+        let mut frame = |id: &str, data: Vec<u8>| {
+            body.extend(id.as_bytes());
+            body.extend((data.len() as u32).to_be_bytes());
+            body.extend([0, 0]);
+            body.extend(data);
+        };
+        for (id, text) in frames {
+            let mut data = vec![0];
+            data.extend(text.as_bytes());
+            frame(id, data);
+        }
+        let mut picture = b"\0image/jpeg\0\x03\0".to_vec();
+        picture.resize(picture.len() + cover, 0xAA);
+        frame("APIC", picture);
+
+        let size = body.len() as u32;
+        let mut tag = b"ID3\x03\0\0".to_vec();
+        // Syncsafe: seven bits per byte
+        tag.extend([21, 14, 7, 0].map(|shift| ((size >> shift) & 0x7F) as u8));
+        tag.extend(body);
+        tag
+    }
+
+    #[test]
+    fn reads_tags_and_stream_bitrate() {
+        let dir = tempfile::tempdir().unwrap();
+        let album = dir.path().join("toe/For Long Tomorrow");
+        fs::create_dir_all(&album).unwrap();
+
+        // A large cover would double a size-based bitrate
+        let mut file = id3_tag(
+            &[
+                ("TIT2", "Goodbye"),
+                ("TPE1", "toe"),
+                ("TALB", "For Long Tomorrow"),
+                ("TRCK", "3/12"),
+                ("TYER", "2009"),
+            ],
+            200_000,
+        );
+        for _ in 0..400 {
+            file.extend(mp3_frame());
+        }
+        let path = album.join("03 Goodbye.mp3");
+        fs::write(&path, file).unwrap();
+        let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        fs::write(album.join("cover.jpg"), [0xAA; 16]).unwrap();
+        fs::write(album.join("broken.mp3"), b"not audio").unwrap();
+
+        let mut report = TrackReport::default();
+        let tracks = scan(dir.path(), &mut report).unwrap();
+
+        assert_eq!(tracks.len(), 1);
+        let track = &tracks[0];
+        assert_eq!(track.title, "Goodbye");
+        assert_eq!(track.artist, "toe");
+        assert_eq!(track.album, "For Long Tomorrow");
+        assert_eq!(track.album_id, "toe/For Long Tomorrow");
+        assert_eq!((track.track, track.tracktotal), (Some(3), Some(12)));
+        assert_eq!(track.year, Some(2009));
+        assert_eq!(track.format, "MP3");
+        // 400 frames of 1152 samples
+        let seconds = track.length.unwrap().as_secs_f64();
+        assert!((seconds - 10.45).abs() < 0.05, "{}", seconds);
+        assert_eq!(track.bitrate, Some(128));
+        assert_eq!(track.added, "2023-11-14 22:13:20");
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::game::GameLibrary;
+use crate::library::{Album, Library};
+use crate::track::Track;
+use anyhow::Result;
+use slug::slugify;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// Fallback for titles that slugify to nothing (e.g. punctuation only)
+const UNTITLED: &str = "untitled";
+
+/// Resolved page slugs for everything the generator writes.
+///
+/// Each kind of page lives in its own content directory, so collisions are
+/// only resolved within a kind. Colliding entries are qualified with their
+/// artist (and album, for tracks) before falling back to numeric suffixes.
+#[derive(Debug, Default)]
+pub struct SlugRegistry {
+    artists: HashMap<String, String>,
+    albums: HashMap<String, String>,
+    tracks: HashMap<String, String>,
+    games: HashMap<String, String>,
+}
+
+impl SlugRegistry {
+    pub fn from_library(library: &Library) -> Self {
+        let mut artists = Vec::new();
+        let mut albums = Vec::new();
+        let mut tracks = Vec::new();
+
+        for (name, artist) in library {
+            artists.push((name.clone(), vec![candidate(&[name])]));
+
+            for album in &artist.albums {
+                albums.push((
+                    album.key(),
+                    vec![
+                        candidate(&[&album.title]),
+                        candidate(&[&album.title, &album.artist]),
+                    ],
+                ));
+
+                for track in &album.tracks {
+                    tracks.push((
+                        track_key(track),
+                        vec![
+                            candidate(&[&track.title]),
+                            candidate(&[&track.title, &track.artist]),
+                            candidate(&[&track.title, &track.artist, &album.title]),
+                        ],
+                    ));
+                }
+            }
+
+            for track in &artist.tracks {
+                tracks.push((
+                    track_key(track),
+                    vec![
+                        candidate(&[&track.title]),
+                        candidate(&[&track.title, &track.artist]),
+                    ],
+                ));
+            }
+        }
+
+        Self {
+            artists: resolve("artist", artists),
+            albums: resolve("album", albums),
+            tracks: resolve("track", tracks),
+            ..Self::default()
+        }
+    }
+
+    pub fn from_games(library: &GameLibrary) -> Self {
+        let games = library
+            .iter()
+            .map(|game| (game.key(), vec![candidate(&[&game.name])]))
+            .collect();
+
+        Self {
+            games: resolve("game", games),
+            ..Self::default()
+        }
+    }
+
+    pub fn artist(&self, name: &str) -> Result<&str> {
+        self.artists
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Artist '{}' has no registered slug", name))
+    }
+
+    pub fn album(&self, album: &Album) -> Result<&str> {
+        self.albums
+            .get(&album.key())
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Album '{}' has no registered slug", album.title))
+    }
+
+    pub fn track(&self, track: &Track) -> Result<&str> {
+        self.tracks
+            .get(&track_key(track))
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Track '{}' has no registered slug", track.title))
+    }
+
+    pub fn game(&self, key: &str) -> Result<&str> {
+        self.games
+            .get(key)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Game {} has no registered slug", key))
+    }
+}
+
+// Beets ids are unique, but exports without them (or hand-merged ones with
+// repeated ids) still tell tracks apart by file, album and position
+fn track_key(track: &Track) -> String {
+    [
+        track.id.as_str(),
+        &track.path,
+        &track.album_id,
+        &track.album,
+        &track.disc.unwrap_or(0).to_string(),
+        &track.track.unwrap_or(0).to_string(),
+        &track.artist,
+        &track.title,
+    ]
+    .join("\0")
+}
+
+fn candidate(parts: &[&str]) -> String {
+    let parts: Vec<&str> = parts.iter().copied().filter(|p| !p.is_empty()).collect();
+    let slug = slugify(parts.join(" "));
+    if slug.is_empty() {
+        UNTITLED.to_string()
+    } else {
+        slug
+    }
+}
+
+/// Assign each key the least qualified candidate slug that is unique.
+///
+/// Candidates are tried level by level across all keys, so a qualified slug
+/// never steals a plain slug from an entry that didn't collide. Entries still
+/// colliding at their most qualified candidate get `-1`, `-2`, ... suffixes in
+/// key order, which keeps the result independent of input order. Entries
+/// sharing a key can only share a page, so they're reported.
+fn resolve<K>(kind: &str, mut entries: Vec<(K, Vec<String>)>) -> HashMap<K, String>
+where
+    K: Eq + Hash + Ord,
+{
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.dedup_by(|a, b| {
+        let duplicate = a.0 == b.0;
+        if duplicate {
+            eprintln!("Duplicate {} '{}' shares a page with another", kind, a.1[0]);
+        }
+        duplicate
+    });
+
+    let levels = entries.iter().map(|(_, c)| c.len()).max().unwrap_or(0);
+    let level_slug = |candidates: &[String], level: usize| -> String {
+        candidates[level.min(candidates.len() - 1)].clone()
+    };
+
+    let mut resolved = HashMap::new();
+    let mut taken = HashSet::new();
+    let mut pending = entries;
+
+    for level in 0..levels {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for (_, candidates) in &pending {
+            *counts.entry(level_slug(candidates, level)).or_default() += 1;
+        }
+
+        let mut colliding = Vec::new();
+        for (key, candidates) in pending {
+            let slug = level_slug(&candidates, level);
+            if counts[&slug] == 1 && !taken.contains(&slug) {
+                taken.insert(slug.clone());
+                resolved.insert(key, slug);
+            } else {
+                colliding.push((key, candidates));
+            }
+        }
+        pending = colliding;
+    }
+
+    for (key, candidates) in pending {
+        let base = level_slug(&candidates, levels);
+        let mut n = 1;
+        loop {
+            let slug = format!("{}-{}", base, n);
+            if taken.insert(slug.clone()) {
+                resolved.insert(key, slug);
+                break;
+            }
+            n += 1;
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::Artist;
+
+    fn track(id: &str, title: &str, artist: &str, album: &str, number: u32) -> Track {
+        Track {
+            id: id.to_string(),
+            title: title.to_string(),
+            artist: artist.to_string(),
+            album: album.to_string(),
+            track: Some(number),
+            path: format!("/music/{}/{}/{}.flac", artist, album, number),
+            ..Track::default()
+        }
+    }
+
+    fn artist(name: &str, albums: &[(&str, &str, &[&str])]) -> (String, Artist) {
+        let mut artist = Artist::new(name.to_string());
+        for (id, title, tracks) in albums {
+            let mut album = Album::new(id.to_string(), title.to_string(), name.to_string());
+            for (i, track_title) in tracks.iter().enumerate() {
+                album.add_track(track(
+                    &format!("{}-{}", id, i),
+                    track_title,
+                    name,
+                    title,
+                    i as u32 + 1,
+                ));
+            }
+            artist.add_album(album);
+        }
+        (name.to_string(), artist)
+    }
+
+    #[test]
+    fn qualifies_same_title_albums_with_their_artist() {
+        let library = Library::from([
+            artist("Lamp", &[("1", "Greatest Hits", &[])]),
+            artist(
+                "toe",
+                &[("2", "Greatest Hits", &[]), ("3", "For Long Tomorrow", &[])],
+            ),
+        ]);
+        let slugs = SlugRegistry::from_library(&library);
+
+        let album = |artist: &str, title: &str| {
+            let album = library[artist]
+                .albums
+                .iter()
+                .find(|album| album.title == title)
+                .unwrap();
+            slugs.album(album).unwrap().to_string()
+        };
+        assert_eq!(album("Lamp", "Greatest Hits"), "greatest-hits-lamp");
+        assert_eq!(album("toe", "Greatest Hits"), "greatest-hits-toe");
+        // Didn't collide, so isn't qualified
+        assert_eq!(album("toe", "For Long Tomorrow"), "for-long-tomorrow");
+    }
+
+    #[test]
+    fn numbers_tracks_still_colliding_when_qualified() {
+        let library = Library::from([artist(
+            "toe",
+            &[("1", "Past", &["Theme", "Interlude", "Interlude", "Theme!"])],
+        )]);
+        let slugs = SlugRegistry::from_library(&library);
+
+        let resolved: Vec<&str> = library["toe"].albums[0]
+            .tracks
+            .iter()
+            .map(|track| slugs.track(track).unwrap())
+            .collect();
+        assert_eq!(
+            resolved,
+            [
+                "theme-toe-past-1",
+                "interlude-toe-past-1",
+                "interlude-toe-past-2",
+                "theme-toe-past-2",
+            ]
+        );
+    }
+
+    #[test]
+    fn tells_apart_tracks_without_unique_ids() {
+        let mut album = Album::new("1".to_string(), "Past".to_string(), "toe".to_string());
+        album.add_track(track("", "Theme", "toe", "Past", 1));
+        album.add_track(track("", "Intro", "toe", "Past", 2));
+        album.add_track(track("7", "Interlude", "toe", "Past", 3));
+        album.add_track(track("7", "Outro", "toe", "Past", 4));
+        let mut toe = Artist::new("toe".to_string());
+        toe.add_album(album);
+        let library = Library::from([("toe".to_string(), toe)]);
+        let slugs = SlugRegistry::from_library(&library);
+
+        let resolved: Vec<&str> = library["toe"].albums[0]
+            .tracks
+            .iter()
+            .map(|track| slugs.track(track).unwrap())
+            .collect();
+        assert_eq!(resolved, ["theme", "intro", "interlude", "outro"]);
+    }
+
+    #[test]
+    fn resolves_independently_of_input_order() {
+        let entries: Vec<(String, Vec<String>)> = ["b", "a", "d", "c"]
+            .iter()
+            .map(|key| (key.to_string(), vec!["same".to_string()]))
+            .collect();
+        let mut reversed = entries.clone();
+        reversed.reverse();
+
+        let forward = resolve("test", entries);
+        assert_eq!(forward, resolve("test", reversed));
+        assert_eq!(forward["a"], "same-1");
+        assert_eq!(forward["d"], "same-4");
+    }
+
+    #[test]
+    fn unregistered_entries_are_errors() {
+        let slugs = SlugRegistry::default();
+        let album = Album::new("1".to_string(), "Past".to_string(), "toe".to_string());
+        assert!(slugs.artist("toe").is_err());
+        assert!(slugs.album(&album).is_err());
+        assert!(slugs.track(&Track::default()).is_err());
+        assert!(slugs.game("Steam:1").is_err());
+    }
+}
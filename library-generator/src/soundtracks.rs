@@ -0,0 +1,253 @@
+//! Links soundtrack albums in the music library to the games they belong to.
+
+use crate::game::{Game, GameLibrary, title_words};
+use crate::library::{Album, Library};
+use crate::slugs::SlugRegistry;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+// Suffixes that mark an album title as a game's soundtrack, longest first so
+// "Original Soundtrack" is stripped whole rather than leaving "Original"
+const SOUNDTRACK_SUFFIXES: &[&str] = &[
+    "original game soundtrack",
+    "original video game soundtrack",
+    "original soundtrack",
+    "official soundtrack",
+    "game soundtrack",
+    "original score",
+    "soundtrack",
+    "ost",
+];
+
+/// Hand-made links for soundtracks the title match gets wrong or misses,
+/// read from a TOML file of album id = appid:
+///
+/// ```toml
+/// [albums]
+/// "1042" = 1903340
+/// # Never link this album to a game
+/// "977" = 0
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Overrides {
+    #[serde(default)]
+    albums: BTreeMap<String, u64>,
+}
+
+impl Overrides {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to read soundtrack overrides '{}': {}",
+                path.display(),
+                e
+            )
+        })?;
+        toml::from_str(&contents).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse soundtrack overrides '{}': {}",
+                path.display(),
+                e
+            )
+        })
+    }
+}
+
+/// A page on the other side of a soundtrack link.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoundtrackLink {
+    pub title: String,
+    pub slug: String,
+}
+
+/// Links in both directions: album key to games, game key to albums.
+#[derive(Debug, Default)]
+pub struct Soundtracks {
+    games: HashMap<String, Vec<SoundtrackLink>>,
+    albums: HashMap<String, Vec<SoundtrackLink>>,
+}
+
+impl Soundtracks {
+    /// Match every album in `library` against `games`. Overrides win; other
+    /// albums that look like soundtracks go to the game whose normalized
+    /// title their own title starts with, preferring the longest title.
+    pub fn link(library: &Library, games: &GameLibrary, overrides: &Overrides) -> Result<Self> {
+        let album_slugs = SlugRegistry::from_library(library);
+        let game_slugs = SlugRegistry::from_games(games);
+
+        let titles: Vec<(Vec<String>, &Game)> = games
+            .iter()
+            .filter(|game| !game.is_empty())
+            .map(|game| (title_words(&game.name), game))
+            .filter(|(words, _)| !words.is_empty())
+            .collect();
+
+        let mut soundtracks = Self::default();
+        for album in library.values().flat_map(|artist| &artist.albums) {
+            let game = match overrides.albums.get(&album.id) {
+                Some(0) => None,
+                Some(appid) => {
+                    let game = games.iter().find(|game| game.appid() == Some(*appid));
+                    if game.is_none() {
+                        eprintln!(
+                            "Soundtrack override for album {} names unknown app {}",
+                            album.id, appid
+                        );
+                    }
+                    game
+                }
+                None => match_title(album, &titles),
+            };
+
+            if let Some(game) = game {
+                soundtracks
+                    .games
+                    .entry(album.key())
+                    .or_default()
+                    .push(SoundtrackLink {
+                        title: game.name.clone(),
+                        slug: game_slugs.game(&game.key())?.to_string(),
+                    });
+                soundtracks
+                    .albums
+                    .entry(game.key())
+                    .or_default()
+                    .push(SoundtrackLink {
+                        title: album.title.clone(),
+                        slug: album_slugs.album(album)?.to_string(),
+                    });
+            }
+        }
+
+        println!("Linked {} soundtracks to games", soundtracks.games.len());
+        Ok(soundtracks)
+    }
+
+    /// Games `album` is the soundtrack of.
+    pub fn games(&self, album: &Album) -> Vec<SoundtrackLink> {
+        self.games.get(&album.key()).cloned().unwrap_or_default()
+    }
+
+    /// Soundtrack albums of `game`.
+    pub fn albums(&self, game: &Game) -> Vec<SoundtrackLink> {
+        self.albums.get(&game.key()).cloned().unwrap_or_default()
+    }
+}
+
+fn match_title<'a>(album: &Album, titles: &[(Vec<String>, &'a Game)]) -> Option<&'a Game> {
+    let mut words = title_words(&album.title);
+    let marked = strip_soundtrack_suffix(&mut words);
+    let is_soundtrack = marked
+        || album.albumtype.eq_ignore_ascii_case("soundtrack")
+        || album.genre.eq_ignore_ascii_case("soundtrack");
+    if !is_soundtrack || words.is_empty() {
+        return None;
+    }
+
+    titles
+        .iter()
+        .filter(|(title, _)| words.starts_with(title))
+        .max_by_key(|(title, _)| title.len())
+        .map(|(_, game)| *game)
+}
+
+// Drops a trailing soundtrack marker, returning whether there was one
+fn strip_soundtrack_suffix(words: &mut Vec<String>) -> bool {
+    for suffix in SOUNDTRACK_SUFFIXES {
+        let suffix: Vec<&str> = suffix.split(' ').collect();
+        if words.len() >= suffix.len() && words[words.len() - suffix.len()..] == suffix[..] {
+            words.truncate(words.len() - suffix.len());
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::SteamGame;
+    use crate::library::Artist;
+
+    fn album(id: &str, title: &str, albumtype: &str) -> Album {
+        let mut album = Album::new(id.to_string(), title.to_string(), "Various".to_string());
+        album.albumtype = albumtype.to_string();
+        album
+    }
+
+    fn game(appid: u64, name: &str) -> Game {
+        Game::from(SteamGame {
+            appid,
+            name: name.to_string(),
+            ..SteamGame::default()
+        })
+    }
+
+    #[test]
+    fn links_soundtracks_by_title_and_override() {
+        let mut artist = Artist::new("Various".to_string());
+        for album in [
+            album("1", "Clair Obscur: Expedition 33 (Original Soundtrack)", ""),
+            album("2", "Portal 2: Songs to Test By", "soundtrack"),
+            album("3", "Hades II", "album"),
+            album("4", "Celeste Original Soundtrack", ""),
+            album("5", "The Legend of Zelda: Breath of the Wild", "soundtrack"),
+        ] {
+            artist.add_album(album);
+        }
+        let library = Library::from([("Various".to_string(), artist)]);
+        let games = vec![
+            game(1903340, "Clair Obscur: Expedition 33"),
+            game(400, "Portal"),
+            game(620, "Portal 2"),
+            game(1145350, "Hades II"),
+            game(504230, "Celeste"),
+            game(1, "Zelda"),
+        ];
+        let overrides: Overrides = toml::from_str(
+            r#"
+[albums]
+"4" = 0
+"5" = 1
+"#,
+        )
+        .unwrap();
+
+        let soundtracks = Soundtracks::link(&library, &games, &overrides).unwrap();
+        let linked = |appid| -> Vec<String> {
+            let game = games.iter().find(|game| game.appid() == Some(appid));
+            soundtracks
+                .albums(game.unwrap())
+                .into_iter()
+                .map(|link| link.title)
+                .collect()
+        };
+
+        assert_eq!(
+            linked(1903340),
+            ["Clair Obscur: Expedition 33 (Original Soundtrack)"]
+        );
+        assert_eq!(linked(620), ["Portal 2: Songs to Test By"]);
+        assert!(linked(400).is_empty());
+        // Not a soundtrack, and overridden away
+        assert!(linked(1145350).is_empty());
+        assert!(linked(504230).is_empty());
+        assert_eq!(linked(1), ["The Legend of Zelda: Breath of the Wild"]);
+
+        let album = library["Various"]
+            .albums
+            .iter()
+            .find(|album| album.id == "1")
+            .unwrap();
+        assert_eq!(
+            soundtracks.games(album),
+            [SoundtrackLink {
+                title: "Clair Obscur: Expedition 33".to_string(),
+                slug: "clair-obscur-expedition-33".to_string(),
+            }]
+        );
+    }
+}
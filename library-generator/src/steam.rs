@@ -1,76 +1,466 @@
-use crate::game::{GameLibrary, SteamLibraryResponse};
+use crate::game::{SteamGamesResponse, SteamLibrary, SteamLibraryResponse};
+use crate::history::{self, PlaytimeHistory, Snapshot};
 use anyhow::Result;
-use std::fs::File;
-use std::io::{BufReader, Write};
-use std::path::Path;
+use chrono::Duration;
+use std::path::PathBuf;
 
-const STEAM_API_URL: &str = "https://api.steampowered.com/IPlayerService/GetOwnedGames/v1";
-const CACHE_FILE: &str = "steam-library.json";
+mod achievements;
+mod artwork;
+mod cache;
+mod error;
+mod http;
+mod store;
+
+pub use cache::CacheMode;
+use cache::{Cached, describe_age};
+pub use error::SteamError;
+use http::Http;
+pub use store::DEFAULT_STORE_URL;
+
+pub const DEFAULT_API_URL: &str = "https://api.steampowered.com";
+const OWNED_GAMES_PATH: &str = "/IPlayerService/GetOwnedGames/v1";
+const LIBRARY_CACHE_FILE: &str = "library.json";
 
 pub struct SteamClient {
     api_key: String,
     steam_id: String,
+    api_url: String,
+    store_url: String,
+    community_media_url: String,
+    cdn_url: String,
+    cache_dir: PathBuf,
+    history_dir: PathBuf,
+    cache_mode: CacheMode,
+    max_age: Duration,
+    store_max_age: Duration,
+    http: Http,
 }
 
 impl SteamClient {
     pub fn new(api_key: String, steam_id: String) -> Self {
-        Self { api_key, steam_id }
+        Self {
+            api_key,
+            steam_id,
+            api_url: DEFAULT_API_URL.to_string(),
+            store_url: DEFAULT_STORE_URL.to_string(),
+            community_media_url: artwork::DEFAULT_COMMUNITY_MEDIA_URL.to_string(),
+            cdn_url: artwork::DEFAULT_CDN_URL.to_string(),
+            cache_dir: cache::default_dir(),
+            history_dir: history::default_dir(),
+            cache_mode: CacheMode::default(),
+            max_age: Duration::hours(24),
+            store_max_age: Duration::days(30),
+            http: Http::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.http = self.http.with_timeout(timeout);
+        self
+    }
+
+    /// Retry rate-limited, failed and timed out requests `retries` times,
+    /// doubling the wait from `backoff` each time.
+    pub fn with_retries(mut self, retries: u32, backoff: std::time::Duration) -> Self {
+        self.http = self.http.with_retries(retries, backoff);
+        self
+    }
+
+    /// Talk to a different Steam Web API host, such as a local stand-in.
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetch store page details from a different host.
+    pub fn with_store_url(mut self, store_url: String) -> Self {
+        self.store_url = store_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Download artwork from one host, such as a local stand-in, instead of
+    /// Steam's community media server and CDN.
+    pub fn with_media_url(mut self, media_url: String) -> Self {
+        let media_url = media_url.trim_end_matches('/').to_string();
+        self.community_media_url = media_url.clone();
+        self.cdn_url = media_url;
+        self
     }
 
-    pub async fn fetch_library(&self) -> Result<GameLibrary> {
-        // Check if cache exists
-        if Path::new(CACHE_FILE).exists() {
-            println!("Loading Steam library from cache: {}", CACHE_FILE);
-            return self.load_from_cache();
+    /// Keep cached responses under `cache_dir/<steam id>/`.
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    /// Keep playtime snapshots under `history_dir/<steam id>/`.
+    pub fn with_history_dir(mut self, history_dir: PathBuf) -> Self {
+        self.history_dir = history_dir;
+        self
+    }
+
+    pub fn with_cache_mode(mut self, cache_mode: CacheMode) -> Self {
+        self.cache_mode = cache_mode;
+        self
+    }
+
+    /// Refetch cached responses older than `max_age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Refetch cached store details older than `max_age`.
+    pub fn with_store_max_age(mut self, max_age: Duration) -> Self {
+        self.store_max_age = max_age;
+        self
+    }
+
+    pub async fn fetch_library(&self) -> Result<SteamLibrary> {
+        let path = self.cache_path(LIBRARY_CACHE_FILE);
+
+        if self.cache_mode != CacheMode::Refresh {
+            let cached = match Cached::<SteamLibrary>::load(&path) {
+                Ok(cached) => cached,
+                // A broken cache is only fatal when there's nothing to fall back on
+                Err(e) if self.cache_mode == CacheMode::Offline => return Err(e),
+                Err(e) => {
+                    eprintln!("Ignoring Steam cache: {}", e);
+                    None
+                }
+            };
+
+            match cached {
+                Some(cached)
+                    if self.cache_mode == CacheMode::Offline || cached.is_fresh(self.max_age) =>
+                {
+                    println!(
+                        "Loaded {} games from cache {} (fetched {} ago)",
+                        cached.data.len(),
+                        path.display(),
+                        describe_age(cached.age())
+                    );
+                    return Ok(cached.data);
+                }
+                Some(_) => println!("Steam library cache is older than the max age"),
+                None if self.cache_mode == CacheMode::Offline => {
+                    return Err(anyhow::anyhow!(
+                        "No cached Steam library for {} at {} (running offline)",
+                        self.steam_id,
+                        path.display()
+                    ));
+                }
+                None => {}
+            }
         }
 
         println!("Fetching Steam library from API...");
-        let library = self.fetch_from_api()?;
+        let library = self.fetch_from_api().await?;
 
-        // Save to cache
-        self.save_to_cache(&library)?;
+        Cached::new(&self.steam_id, &library).save(&path)?;
+        println!("Saved Steam library to {}", path.display());
+
+        // Only fresh API data makes a snapshot, dated the day it was fetched
+        Snapshot::of(&library, history::today()).save(&self.history_path())?;
 
         Ok(library)
     }
 
-    fn fetch_from_api(&self) -> Result<GameLibrary> {
+    async fn fetch_from_api(&self) -> Result<SteamLibrary, SteamError> {
         let url = format!(
-            "{}?key={}&steamid={}&include_appinfo=true&include_played_free_games=false",
-            STEAM_API_URL, self.api_key, self.steam_id
+            "{}{}?key={}&steamid={}&include_appinfo=true&include_played_free_games=false",
+            self.api_url, OWNED_GAMES_PATH, self.api_key, self.steam_id
         );
 
-        let response = ureq::get(&url).call()?;
-        let steam_response: SteamLibraryResponse = response.into_json()?;
+        let steam_response: SteamLibraryResponse =
+            self.http.get_json("GetOwnedGames", &url).await?;
 
-        println!(
-            "Fetched {} games from Steam API",
-            steam_response.response.game_count
-        );
+        let games = match steam_response.response {
+            SteamGamesResponse {
+                games: Some(games), ..
+            } => games,
+            SteamGamesResponse {
+                game_count: Some(0),
+                ..
+            } => Vec::new(),
+            _ => {
+                return Err(SteamError::PrivateProfile {
+                    steam_id: self.steam_id.clone(),
+                });
+            }
+        };
 
-        Ok(steam_response.response.games)
+        println!("Fetched {} games from Steam API", games.len());
+        Ok(games)
     }
 
-    fn load_from_cache(&self) -> Result<GameLibrary> {
-        let file = File::open(CACHE_FILE)?;
-        let reader = BufReader::new(file);
-        let games: GameLibrary = serde_json::from_reader(reader)?;
-        println!("Loaded {} games from cache", games.len());
-        Ok(games)
+    /// Playtime snapshots recorded by earlier fetches for this account.
+    pub fn playtime_history(&self) -> Result<PlaytimeHistory> {
+        PlaytimeHistory::load(&self.history_path())
     }
 
-    fn save_to_cache(&self, library: &GameLibrary) -> Result<()> {
-        let json = serde_json::to_string_pretty(library)?;
-        let mut file = File::create(CACHE_FILE)?;
-        file.write_all(json.as_bytes())?;
-        println!("Saved Steam library to {}", CACHE_FILE);
-        Ok(())
+    fn history_path(&self) -> PathBuf {
+        self.history_dir.join(&self.steam_id)
+    }
+
+    fn cache_path(&self, file: &str) -> PathBuf {
+        self.cache_dir.join(&self.steam_id).join(file)
     }
 
-    pub fn clear_cache() -> Result<()> {
-        if Path::new(CACHE_FILE).exists() {
-            std::fs::remove_file(CACHE_FILE)?;
-            println!("Cleared Steam library cache");
+    /// Remove everything cached for this Steam account.
+    pub fn clear_cache(&self) -> Result<()> {
+        let dir = self.cache_dir.join(&self.steam_id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+            println!("Cleared Steam cache in {}", dir.display());
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_steam::{FakeSteam, OWNED_GAMES, Route, owned_games};
+    use crate::game::Game;
+    use crate::generator::testing::TestSite;
+
+    fn client(server: &FakeSteam) -> SteamClient {
+        SteamClient::new("test-key".to_string(), "76561197960287930".to_string())
+            .with_api_url(server.url().to_string())
+            .with_retries(2, std::time::Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn fetches_owned_games() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+
+        let games = client(&server).fetch_from_api().await.unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].name, "Stardew Valley");
+        assert_eq!(games[0].playtime_deck_forever, 1800);
+        assert_eq!(games[1].img_icon_url, "");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with(OWNED_GAMES));
+        assert!(requests[0].contains("steamid=76561197960287930"));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_rate_limits() {
+        let server = FakeSteam::start(vec![
+            Route::status(OWNED_GAMES, 503, "Service Unavailable").times(1),
+            Route::status(OWNED_GAMES, 429, "Too Many Requests").times(1),
+            Route::json(OWNED_GAMES, owned_games()),
+        ]);
+
+        let games = client(&server).fetch_from_api().await.unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_last_retry() {
+        let server = FakeSteam::start(vec![Route::status(OWNED_GAMES, 500, "Oops")]);
+
+        let err = client(&server).fetch_from_api().await.unwrap_err();
+
+        assert!(matches!(err, SteamError::Status { status: 500 }), "{}", err);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn rejected_keys_are_not_retried() {
+        let server = FakeSteam::start(vec![Route::status(OWNED_GAMES, 403, "Forbidden")]);
+
+        let err = client(&server).fetch_from_api().await.unwrap_err();
+
+        assert!(matches!(err, SteamError::BadApiKey), "{}", err);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn private_profiles_are_reported() {
+        let server = FakeSteam::start(vec![Route::json(
+            OWNED_GAMES,
+            serde_json::json!({ "response": {} }),
+        )]);
+
+        let err = client(&server).fetch_from_api().await.unwrap_err();
+
+        assert!(matches!(err, SteamError::PrivateProfile { .. }), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn accounts_without_games_are_empty() {
+        let server = FakeSteam::start(vec![Route::json(
+            OWNED_GAMES,
+            serde_json::json!({ "response": { "game_count": 0 } }),
+        )]);
+
+        let games = client(&server).fetch_from_api().await.unwrap();
+
+        assert!(games.is_empty());
+    }
+
+    #[tokio::test]
+    async fn malformed_bodies_fail_the_fetch() {
+        for body in ["not json", "{\"response\": {\"games\": 3}}"] {
+            let server = FakeSteam::start(vec![Route::status(OWNED_GAMES, 200, body)]);
+
+            let err = client(&server).fetch_from_api().await.unwrap_err();
+
+            assert!(matches!(err, SteamError::InvalidResponse { .. }), "{}", err);
+            assert!(err.to_string().contains("GetOwnedGames"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_servers_are_network_errors() {
+        // Bind and release a port so nothing is listening on it
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = SteamClient::new("test-key".to_string(), "1".to_string())
+            .with_api_url(format!("http://127.0.0.1:{}", port))
+            .with_retries(1, std::time::Duration::from_millis(1));
+
+        let err = client.fetch_from_api().await.unwrap_err();
+
+        assert!(matches!(err, SteamError::Network(_)), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn errors_never_include_the_api_key() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = SteamClient::new("s3cr3t-k3y".to_string(), "1".to_string())
+            .with_api_url(format!("http://127.0.0.1:{}", port))
+            .with_cache_dir(tempfile::tempdir().unwrap().path().to_path_buf())
+            .with_retries(0, std::time::Duration::from_millis(1));
+
+        let err = client.fetch_library().await.unwrap_err();
+
+        assert!(!format!("{}", err).contains("s3cr3t-k3y"), "{}", err);
+        assert!(!format!("{:?}", err).contains("s3cr3t-k3y"), "{:?}", err);
+        assert!(!format!("{:#}", err).contains("s3cr3t-k3y"), "{:#}", err);
+    }
+
+    // Write a library cache for the test account, fetched `hours` ago
+    fn write_cache(dir: &std::path::Path, hours: i64, games: SteamLibrary) {
+        let mut cached = Cached::new("76561197960287930", games);
+        cached.fetched_at -= Duration::hours(hours);
+        cached
+            .save(&dir.join("76561197960287930").join(LIBRARY_CACHE_FILE))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn fresh_cache_skips_the_api() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+        let dir = tempfile::tempdir().unwrap();
+        write_cache(dir.path(), 1, Vec::new());
+
+        let games = client(&server)
+            .with_cache_dir(dir.path().to_path_buf())
+            .with_history_dir(dir.path().join("history"))
+            .fetch_library()
+            .await
+            .unwrap();
+
+        assert!(games.is_empty());
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stale_cache_is_refetched_and_saved() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+        let dir = tempfile::tempdir().unwrap();
+        write_cache(dir.path(), 48, Vec::new());
+
+        let client = client(&server)
+            .with_cache_dir(dir.path().to_path_buf())
+            .with_history_dir(dir.path().join("history"));
+        let games = client.fetch_library().await.unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(server.requests().len(), 1);
+
+        let cached = Cached::<SteamLibrary>::load(&client.cache_path(LIBRARY_CACHE_FILE))
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.steam_id, "76561197960287930");
+        assert_eq!(cached.data.len(), 2);
+        assert!(cached.is_fresh(Duration::minutes(1)));
+    }
+
+    #[tokio::test]
+    async fn refresh_ignores_a_fresh_cache() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+        let dir = tempfile::tempdir().unwrap();
+        write_cache(dir.path(), 1, Vec::new());
+
+        let games = client(&server)
+            .with_cache_dir(dir.path().to_path_buf())
+            .with_history_dir(dir.path().join("history"))
+            .with_cache_mode(CacheMode::Refresh)
+            .fetch_library()
+            .await
+            .unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn offline_uses_any_cache_and_requires_one() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+        let dir = tempfile::tempdir().unwrap();
+        let client = client(&server)
+            .with_cache_dir(dir.path().to_path_buf())
+            .with_history_dir(dir.path().join("history"))
+            .with_cache_mode(CacheMode::Offline);
+
+        let err = client.fetch_library().await.unwrap_err();
+        assert!(err.to_string().contains("offline"), "{}", err);
+
+        write_cache(dir.path(), 24 * 365, Vec::new());
+        assert!(client.fetch_library().await.unwrap().is_empty());
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn generates_game_pages_from_the_api() {
+        let server = FakeSteam::start(vec![Route::json(OWNED_GAMES, owned_games())]);
+        let games = client(&server).fetch_from_api().await.unwrap();
+
+        let site = TestSite::new();
+        site.generate_games(&games.into_iter().map(Game::from).collect())
+            .await;
+
+        let page = site.read("games/stardew-valley.md");
+        assert!(page.contains("Stardew Valley"));
+        assert!(site.path("games/portal-2.md").exists());
+        assert!(site.path("games/_index.md").exists());
+
+        // 3000 minutes on Windows, 2400 on Linux of which 1800 on the Deck
+        assert!(page.contains("**By Platform:** Windows 50.0h, Linux 10.0h, Steam Deck 30.0h"));
+        let index = site.read("games/_index.md");
+        assert!(index.contains("- **Steam Deck:** 30.0 hours"));
+        let deck = site.read("game-lists/played-on-deck.md");
+        assert!(
+            deck.contains(
+                "[Stardew Valley](/games/stardew-valley/) - 30.0h on Deck (33% of 90.0h)"
+            )
+        );
+        assert!(!deck.contains("Portal 2"));
+    }
+}
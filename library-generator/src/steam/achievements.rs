@@ -0,0 +1,192 @@
+use super::cache::{CacheMode, Cached};
+use super::{SteamClient, SteamError};
+use crate::game::{GameAchievements, PlayerAchievementsResponse, SteamGame, SteamLibrary};
+use anyhow::Result;
+
+const PLAYER_ACHIEVEMENTS_PATH: &str = "/ISteamUserStats/GetPlayerAchievements/v1";
+const ACHIEVEMENTS_CACHE_DIR: &str = "achievements";
+
+impl SteamClient {
+    /// Attach achievements to every game in `library`, one request per game,
+    /// cached per game next to the library cache. Games without achievements
+    /// are left at `None`, as are games that fail to fetch.
+    pub async fn fetch_achievements(&self, library: &mut SteamLibrary) -> Result<()> {
+        let mut fetched = 0;
+        let mut cached = 0;
+
+        for game in library.iter_mut() {
+            let path = self.cache_path(&format!("{}/{}.json", ACHIEVEMENTS_CACHE_DIR, game.appid));
+
+            let entry = Cached::<Option<GameAchievements>>::load(&path).unwrap_or_else(|e| {
+                eprintln!("Ignoring achievements cache: {}", e);
+                None
+            });
+            if let Some(entry) = entry
+                && self.achievements_cache_usable(&entry, game)
+            {
+                game.achievements = entry.data;
+                cached += 1;
+                continue;
+            }
+
+            if self.cache_mode == CacheMode::Offline {
+                continue;
+            }
+
+            match self.fetch_game_achievements(game.appid).await {
+                Ok(achievements) => {
+                    Cached::new(&self.steam_id, &achievements).save(&path)?;
+                    game.achievements = achievements;
+                    fetched += 1;
+                }
+                // The library fetch worked with this key, so this means the
+                // account's achievements aren't public; no point asking again
+                Err(SteamError::BadApiKey) => {
+                    eprintln!("Steam refused achievements access; skipping the rest");
+                    break;
+                }
+                Err(e) => eprintln!("Skipping achievements for {}: {}", game.name, e),
+            }
+        }
+
+        println!(
+            "Loaded achievements for {} games ({} fetched, {} from cache)",
+            fetched + cached,
+            fetched,
+            cached
+        );
+        Ok(())
+    }
+
+    /// A cached entry stays good while it's fresh, or for as long as the
+    /// game hasn't been played since it was fetched.
+    fn achievements_cache_usable(
+        &self,
+        entry: &Cached<Option<GameAchievements>>,
+        game: &SteamGame,
+    ) -> bool {
+        match self.cache_mode {
+            CacheMode::Offline => true,
+            CacheMode::Refresh => false,
+            CacheMode::Normal => {
+                entry.is_fresh(self.max_age)
+                    || entry.fetched_at.timestamp() >= game.rtime_last_played as i64
+            }
+        }
+    }
+
+    async fn fetch_game_achievements(
+        &self,
+        appid: u64,
+    ) -> Result<Option<GameAchievements>, SteamError> {
+        let url = format!(
+            "{}{}?appid={}&key={}&steamid={}&l=english",
+            self.api_url, PLAYER_ACHIEVEMENTS_PATH, appid, self.api_key, self.steam_id
+        );
+
+        // Steam answers 400 for games that have no stats at all
+        let response: PlayerAchievementsResponse =
+            match self.http.get_json("GetPlayerAchievements", &url).await {
+                Err(SteamError::Status { status: 400 }) => return Ok(None),
+                result => result?,
+            };
+
+        let stats = response.playerstats;
+        if !stats.success || stats.achievements.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(GameAchievements {
+            achievements: stats.achievements,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_steam::{
+        FakeSteam, NO_STATS, OWNED_GAMES, PLAYER_ACHIEVEMENTS, Route, owned_games,
+        player_achievements,
+    };
+    use std::path::Path;
+
+    fn routes() -> Vec<Route> {
+        vec![
+            Route::json(OWNED_GAMES, owned_games()),
+            Route::json(
+                format!("{}?appid=413150", PLAYER_ACHIEVEMENTS),
+                player_achievements(),
+            ),
+            Route::status(format!("{}?appid=620", PLAYER_ACHIEVEMENTS), 400, NO_STATS),
+        ]
+    }
+
+    fn client(server: &FakeSteam, cache_dir: &Path) -> SteamClient {
+        SteamClient::new("test-key".to_string(), "76561197960287930".to_string())
+            .with_api_url(server.url().to_string())
+            .with_cache_dir(cache_dir.to_path_buf())
+            .with_history_dir(cache_dir.join("history"))
+            .with_retries(0, std::time::Duration::from_millis(1))
+    }
+
+    fn achievement_requests(server: &FakeSteam) -> usize {
+        server
+            .requests()
+            .iter()
+            .filter(|r| r.starts_with(PLAYER_ACHIEVEMENTS))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn attaches_achievements_to_games() {
+        let server = FakeSteam::start(routes());
+        let dir = tempfile::tempdir().unwrap();
+        let client = client(&server, dir.path());
+
+        let mut games = client.fetch_library().await.unwrap();
+        client.fetch_achievements(&mut games).await.unwrap();
+
+        let stardew = games[0].achievements.as_ref().unwrap();
+        assert_eq!((stardew.unlocked(), stardew.total()), (2, 3));
+        assert_eq!(format!("{:.0}", stardew.percent()), "67");
+        assert_eq!(stardew.unlocked_by_date()[0].name, "Cowpoke");
+        assert!(games[1].achievements.is_none());
+    }
+
+    #[tokio::test]
+    async fn reuses_cached_achievements_until_played_again() {
+        let server = FakeSteam::start(routes());
+        let dir = tempfile::tempdir().unwrap();
+        let client = client(&server, dir.path()).with_max_age(chrono::Duration::zero());
+
+        let mut games = client.fetch_library().await.unwrap();
+        client.fetch_achievements(&mut games).await.unwrap();
+        assert_eq!(achievement_requests(&server), 2);
+
+        // Expired, but neither game has been played since the fetch
+        client.fetch_achievements(&mut games).await.unwrap();
+        assert_eq!(achievement_requests(&server), 2);
+        assert!(games[0].achievements.is_some());
+
+        games[0].rtime_last_played = chrono::Utc::now().timestamp() as u64 + 60;
+        client.fetch_achievements(&mut games).await.unwrap();
+        assert_eq!(achievement_requests(&server), 3);
+    }
+
+    #[tokio::test]
+    async fn offline_only_reads_the_cache() {
+        let server = FakeSteam::start(routes());
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut games = client(&server, dir.path()).fetch_library().await.unwrap();
+        client(&server, dir.path())
+            .with_cache_mode(CacheMode::Offline)
+            .fetch_achievements(&mut games)
+            .await
+            .unwrap();
+
+        assert_eq!(achievement_requests(&server), 0);
+        assert!(games[0].achievements.is_none());
+    }
+}
@@ -0,0 +1,154 @@
+use super::SteamClient;
+use super::cache::CacheMode;
+use crate::game::{GameArtwork, SteamGame, SteamLibrary};
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_COMMUNITY_MEDIA_URL: &str = "https://media.steampowered.com";
+pub const DEFAULT_CDN_URL: &str = "https://cdn.akamai.steamstatic.com";
+
+// Where images go under the static directory, and the site path they're served at
+const ARTWORK_DIR: &str = "games";
+
+impl SteamClient {
+    /// Download each game's icon, header and capsule images into
+    /// `static_dir/games/<appid>/` and record their site paths on the game.
+    /// Files already on disk are never fetched again.
+    pub async fn download_artwork(
+        &self,
+        library: &mut SteamLibrary,
+        static_dir: &Path,
+    ) -> Result<()> {
+        let mut downloaded = 0;
+
+        for game in library.iter_mut() {
+            let dir = static_dir.join(ARTWORK_DIR).join(game.appid.to_string());
+            let mut artwork = GameArtwork::default();
+
+            for (name, url, slot) in [
+                ("icon.jpg", self.icon_url(game), &mut artwork.icon),
+                (
+                    "header.jpg",
+                    Some(format!(
+                        "{}/steam/apps/{}/header.jpg",
+                        self.cdn_url, game.appid
+                    )),
+                    &mut artwork.header,
+                ),
+                (
+                    "capsule.jpg",
+                    Some(format!(
+                        "{}/steam/apps/{}/capsule_231x87.jpg",
+                        self.cdn_url, game.appid
+                    )),
+                    &mut artwork.capsule,
+                ),
+            ] {
+                let path = dir.join(name);
+                let site_path = format!("/{}/{}/{}", ARTWORK_DIR, game.appid, name);
+
+                if path.exists() {
+                    *slot = Some(site_path);
+                    continue;
+                }
+                let Some(url) = url else { continue };
+                if self.cache_mode == CacheMode::Offline {
+                    continue;
+                }
+
+                match self.http.get(&url).await {
+                    Ok(bytes) => {
+                        fs::create_dir_all(&dir)?;
+                        // Write then rename, so an interrupted run never
+                        // leaves a truncated image that would be skipped later
+                        let partial = path.with_extension("part");
+                        fs::write(&partial, bytes)?;
+                        fs::rename(&partial, &path)?;
+                        *slot = Some(site_path);
+                        downloaded += 1;
+                    }
+                    Err(e) => eprintln!("Skipping {} for {}: {}", name, game.name, e),
+                }
+            }
+
+            game.artwork = Some(artwork);
+        }
+
+        println!(
+            "Downloaded {} images into {}",
+            downloaded,
+            static_dir.join(ARTWORK_DIR).display()
+        );
+        Ok(())
+    }
+
+    fn icon_url(&self, game: &SteamGame) -> Option<String> {
+        if game.img_icon_url.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{}/steamcommunity/public/images/apps/{}/{}.jpg",
+            self.community_media_url, game.appid, game.img_icon_url
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_steam::{FakeSteam, OWNED_GAMES, Route, owned_games};
+
+    #[tokio::test]
+    async fn downloads_artwork_once() {
+        // Portal 2 has no icon hash and no capsule on the fake CDN
+        let server = FakeSteam::start(vec![
+            Route::json(OWNED_GAMES, owned_games()),
+            Route::status(
+                "/steamcommunity/public/images/apps/413150/",
+                200,
+                "icon bytes",
+            ),
+            Route::status("/steam/apps/413150/", 200, "image bytes"),
+            Route::status("/steam/apps/620/header.jpg", 200, "header bytes"),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let static_dir = dir.path().join("static");
+        let client = SteamClient::new("test-key".to_string(), "1".to_string())
+            .with_api_url(server.url().to_string())
+            .with_media_url(server.url().to_string())
+            .with_cache_dir(dir.path().join("cache"))
+            .with_history_dir(dir.path().join("history"))
+            .with_retries(0, std::time::Duration::from_millis(1));
+
+        let mut games = client.fetch_library().await.unwrap();
+        client
+            .download_artwork(&mut games, &static_dir)
+            .await
+            .unwrap();
+
+        let stardew = games[0].artwork.as_ref().unwrap();
+        assert_eq!(stardew.icon.as_deref(), Some("/games/413150/icon.jpg"));
+        assert_eq!(stardew.thumbnail(), Some("/games/413150/capsule.jpg"));
+        assert_eq!(
+            fs::read_to_string(static_dir.join("games/413150/header.jpg")).unwrap(),
+            "image bytes"
+        );
+
+        let portal = games[1].artwork.as_ref().unwrap();
+        assert!(portal.icon.is_none() && portal.capsule.is_none());
+        assert_eq!(portal.thumbnail(), Some("/games/620/header.jpg"));
+
+        let requests = server.requests().len();
+        client
+            .download_artwork(&mut games, &static_dir)
+            .await
+            .unwrap();
+        // Only the capsule that 404'd is asked for again
+        assert_eq!(server.requests().len(), requests + 1);
+        assert_eq!(
+            games[0].artwork.as_ref().unwrap().header.as_deref(),
+            Some("/games/413150/header.jpg")
+        );
+    }
+}
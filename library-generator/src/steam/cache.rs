@@ -0,0 +1,92 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// How `SteamClient` uses its on-disk cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Use the cache while it's younger than the max age, fetch otherwise
+    #[default]
+    Normal,
+    /// Never touch the network; a missing cache is an error
+    Offline,
+    /// Always fetch and overwrite the cache
+    Refresh,
+}
+
+/// API data as saved on disk, with the account and time it was fetched for.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cached<T> {
+    pub steam_id: String,
+    pub fetched_at: DateTime<Utc>,
+    pub data: T,
+}
+
+impl<T> Cached<T> {
+    pub fn new(steam_id: &str, data: T) -> Self {
+        Self {
+            steam_id: steam_id.to_string(),
+            fetched_at: Utc::now(),
+            data,
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.fetched_at
+    }
+
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        self.age() < max_age
+    }
+}
+
+impl<T: DeserializeOwned> Cached<T> {
+    /// Read a cache file, `None` if there isn't one yet.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("Failed to read cache '{}': {}", path.display(), e))
+    }
+}
+
+impl<T: Serialize> Cached<T> {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let mut file = File::create(path)?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// `$XDG_CACHE_HOME/library-generator/steam` (or the platform equivalent),
+/// falling back to `.cache` in the working directory.
+pub fn default_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from(".cache"))
+        .join("library-generator")
+        .join("steam")
+}
+
+/// Human-readable cache age, e.g. "3h" or "2d".
+pub fn describe_age(age: Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
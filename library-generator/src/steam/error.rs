@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Why a Steam Web API request failed.
+#[derive(Debug)]
+pub enum SteamError {
+    /// Steam rejected the API key (401/403)
+    BadApiKey,
+    /// The account's game details aren't public, so Steam returned no games
+    PrivateProfile { steam_id: String },
+    /// Steam answered with an error status, after any retries
+    Status { status: u16 },
+    /// The request never got an answer: DNS, connection or timeout
+    Network(reqwest::Error),
+    /// Steam answered 200 with a body we couldn't read
+    InvalidResponse {
+        endpoint: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for SteamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteamError::BadApiKey => write!(
+                f,
+                "Steam rejected the API key; check STEAM_API_KEY (https://steamcommunity.com/dev/apikey)"
+            ),
+            SteamError::PrivateProfile { steam_id } => write!(
+                f,
+                "Steam returned no games for {}; is the profile's game details privacy setting public?",
+                steam_id
+            ),
+            SteamError::Status { status } => write!(f, "Steam API returned HTTP {}", status),
+            SteamError::Network(e) => write!(f, "Could not reach the Steam API: {}", e),
+            SteamError::InvalidResponse { endpoint, message } => {
+                write!(f, "Unexpected {} response: {}", endpoint, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SteamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SteamError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::error::SteamError;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+// Longest we'll honour a Retry-After header for
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+// Longest we'll back off for between retries
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Async HTTP client for the Steam Web API that retries rate limits (429),
+/// server errors (5xx) and network failures with exponential backoff.
+pub struct Http {
+    client: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl Http {
+    pub fn new() -> Self {
+        Self {
+            client: build_client(Duration::from_secs(30)),
+            retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Give up on a request after `timeout`, counting from connecting.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = build_client(timeout);
+        self
+    }
+
+    /// Retry up to `retries` times, waiting `backoff` before the first retry
+    /// and doubling it each time, up to a minute.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// GET `url` and decode the JSON body; `endpoint` names it in errors.
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        url: &str,
+    ) -> Result<T, SteamError> {
+        let body = self.get(url).await?;
+        serde_json::from_slice(&body).map_err(|e| SteamError::InvalidResponse {
+            endpoint,
+            message: e.to_string(),
+        })
+    }
+
+    /// GET `url` and return the raw body.
+    pub async fn get(&self, url: &str) -> Result<Vec<u8>, SteamError> {
+        let mut attempt = 0;
+        loop {
+            let delay = self.backoff_delay(attempt);
+
+            let (error, delay) = match self.client.get(url).send().await {
+                Ok(response) if response.status().is_success() => match response.bytes().await {
+                    Ok(body) => return Ok(body.to_vec()),
+                    Err(e) => (network_error(e), delay),
+                },
+                Ok(response) => {
+                    let status = response.status();
+                    match status {
+                        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                            return Err(SteamError::BadApiKey);
+                        }
+                        StatusCode::TOO_MANY_REQUESTS => {
+                            let delay = retry_after(&response).unwrap_or(delay);
+                            (SteamError::Status { status: 429 }, delay)
+                        }
+                        _ if status.is_server_error() => (
+                            SteamError::Status {
+                                status: status.as_u16(),
+                            },
+                            delay,
+                        ),
+                        _ => {
+                            return Err(SteamError::Status {
+                                status: status.as_u16(),
+                            });
+                        }
+                    }
+                }
+                Err(e) => (network_error(e), delay),
+            };
+
+            if attempt >= self.retries {
+                return Err(error);
+            }
+
+            attempt += 1;
+            eprintln!(
+                "{}; retrying in {:.1}s ({}/{})",
+                error,
+                delay.as_secs_f64(),
+                attempt,
+                self.retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt)
+            .and_then(|factor| self.backoff.checked_mul(factor))
+            .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+    }
+}
+
+// Request URLs carry the API key, so they're dropped from errors that could
+// end up in logs
+fn network_error(e: reqwest::Error) -> SteamError {
+    SteamError::Network(e.without_url())
+}
+
+fn build_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(timeout.min(Duration::from_secs(10)))
+        .user_agent(concat!("library-generator/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("HTTP client configuration is valid")
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds: u64 = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_a_minute() {
+        let http = Http::new().with_retries(100, Duration::from_secs(1));
+        let delays: Vec<u64> = (0..8)
+            .map(|attempt| http.backoff_delay(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+
+        // Past where the factor or the duration would overflow
+        assert_eq!(http.backoff_delay(31), MAX_BACKOFF);
+        assert_eq!(http.backoff_delay(32), MAX_BACKOFF);
+        assert_eq!(http.backoff_delay(99), MAX_BACKOFF);
+        let http = Http::new().with_retries(100, Duration::MAX);
+        assert_eq!(http.backoff_delay(1), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,163 @@
+use super::SteamClient;
+use super::cache::{CacheMode, Cached};
+use crate::game::{AppDetailsResponse, SteamLibrary, StoreDetails};
+use anyhow::Result;
+
+pub const DEFAULT_STORE_URL: &str = "https://store.steampowered.com";
+const APP_DETAILS_PATH: &str = "/api/appdetails";
+// Store data isn't tied to an account, so it's shared between Steam IDs
+const STORE_CACHE_DIR: &str = "store";
+
+impl SteamClient {
+    /// Attach store page metadata (genres, developers, release date, ...) to
+    /// every game in `library`. Each app's details are cached on their own
+    /// for the store max age, which is much longer than the library's since
+    /// they rarely change. Delisted apps are cached as having no details.
+    pub async fn fetch_store_details(&self, library: &mut SteamLibrary) -> Result<()> {
+        let mut fetched = 0;
+        let mut cached = 0;
+
+        for game in library.iter_mut() {
+            let path = self
+                .cache_dir
+                .join(STORE_CACHE_DIR)
+                .join(format!("{}.json", game.appid));
+
+            let entry = Cached::<Option<StoreDetails>>::load(&path).unwrap_or_else(|e| {
+                eprintln!("Ignoring store cache: {}", e);
+                None
+            });
+            if let Some(entry) = entry
+                && match self.cache_mode {
+                    CacheMode::Offline => true,
+                    CacheMode::Refresh => false,
+                    CacheMode::Normal => entry.is_fresh(self.store_max_age),
+                }
+            {
+                game.store = entry.data;
+                cached += 1;
+                continue;
+            }
+
+            if self.cache_mode == CacheMode::Offline {
+                continue;
+            }
+
+            match self.fetch_app_details(game.appid).await {
+                Ok(details) => {
+                    Cached::new(&self.steam_id, &details).save(&path)?;
+                    game.store = details;
+                    fetched += 1;
+                }
+                Err(e) => eprintln!("Skipping store details for {}: {}", game.name, e),
+            }
+        }
+
+        println!(
+            "Loaded store details for {} games ({} fetched, {} from cache)",
+            fetched + cached,
+            fetched,
+            cached
+        );
+        Ok(())
+    }
+
+    async fn fetch_app_details(&self, appid: u64) -> Result<Option<StoreDetails>> {
+        let url = format!(
+            "{}{}?appids={}&l=english",
+            self.store_url, APP_DETAILS_PATH, appid
+        );
+
+        let mut response: AppDetailsResponse = self.http.get_json("appdetails", &url).await?;
+
+        Ok(response
+            .remove(&appid.to_string())
+            .filter(|entry| entry.success)
+            .and_then(|entry| entry.data)
+            .map(StoreDetails::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_steam::{APP_DETAILS, FakeSteam, OWNED_GAMES, Route, app_details, owned_games};
+    use crate::game::Game;
+    use crate::generator::testing::TestSite;
+    use std::path::Path;
+
+    fn routes() -> Vec<Route> {
+        vec![
+            Route::json(OWNED_GAMES, owned_games()),
+            Route::json(format!("{}?appids=413150", APP_DETAILS), app_details()),
+            // Delisted apps come back unsuccessful
+            Route::json(
+                format!("{}?appids=620", APP_DETAILS),
+                serde_json::json!({ "620": { "success": false } }),
+            ),
+        ]
+    }
+
+    fn client(server: &FakeSteam, cache_dir: &Path) -> SteamClient {
+        SteamClient::new("test-key".to_string(), "76561197960287930".to_string())
+            .with_api_url(server.url().to_string())
+            .with_store_url(server.url().to_string())
+            .with_cache_dir(cache_dir.to_path_buf())
+            .with_history_dir(cache_dir.join("history"))
+            .with_retries(0, std::time::Duration::from_millis(1))
+    }
+
+    fn store_requests(server: &FakeSteam) -> usize {
+        server
+            .requests()
+            .iter()
+            .filter(|r| r.starts_with(APP_DETAILS))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn attaches_store_details_and_caches_them() {
+        let server = FakeSteam::start(routes());
+        let dir = tempfile::tempdir().unwrap();
+        let client = client(&server, dir.path());
+
+        let mut games = client.fetch_library().await.unwrap();
+        client.fetch_store_details(&mut games).await.unwrap();
+
+        let store = games[0].store.as_ref().unwrap();
+        assert_eq!(store.developers, ["ConcernedApe"]);
+        assert_eq!(store.genres, ["Indie", "Simulation"]);
+        assert_eq!(store.categories, ["Single-player", "Multi-player"]);
+        assert_eq!(store.release_date, "26 Feb, 2016");
+        assert!(games[1].store.is_none());
+        assert_eq!(store_requests(&server), 2);
+
+        // Both answers, including the delisted app's, come from the cache now
+        client.fetch_store_details(&mut games).await.unwrap();
+        assert_eq!(store_requests(&server), 2);
+        assert!(games[0].store.is_some());
+    }
+
+    #[tokio::test]
+    async fn generates_genre_and_developer_pages() {
+        let server = FakeSteam::start(routes());
+        let dir = tempfile::tempdir().unwrap();
+        let client = client(&server, &dir.path().join("cache"));
+
+        let mut games = client.fetch_library().await.unwrap();
+        client.fetch_store_details(&mut games).await.unwrap();
+
+        let site = TestSite::new();
+        site.generate_games(&games.into_iter().map(Game::from).collect())
+            .await;
+
+        let developer = site.read("developers/concernedape.md");
+        assert!(developer.contains("[Stardew Valley](/games/stardew-valley/)"));
+        assert!(site.path("game-genres/simulation.md").exists());
+        assert!(site.path("game-genres/_index.md").exists());
+
+        let page = site.read("games/stardew-valley.md");
+        assert!(page.contains("**Developer:** [ConcernedApe](/developers/concernedape/)"));
+        assert!(page.contains("**Released:** 26 Feb, 2016"));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::game::{Game, GameLibrary};
+use crate::library::{Album, Artist, Library};
+use crate::track::Track;
+use slug::slugify;
+use std::collections::BTreeMap;
+
+/// One value of a free-text track field (a genre, a label, ...) and
+/// everything in the library that carries it.
+pub struct Term<'a> {
+    pub name: String,
+    pub slug: String,
+    pub artists: Vec<&'a Artist>,
+    pub albums: Vec<(&'a Artist, &'a Album)>,
+    pub tracks: Vec<(&'a Artist, Option<&'a Album>, &'a Track)>,
+}
+
+// beets' multi-value delimiter. Commas and slashes appear inside names
+// ("Lennon, John", "AC/DC"), so they never separate credits.
+const CREDIT_SEPARATOR: char = ';';
+
+/// The people credited in a field such as composer, e.g. "A; B".
+pub fn credits(value: &str) -> Vec<&str> {
+    value
+        .split(CREDIT_SEPARATOR)
+        .map(str::trim)
+        .filter(|credit| !credit.is_empty())
+        .collect()
+}
+
+/// Group the library by every value `field` returns for each track.
+///
+/// Values are grouped by slug, so "Hip-Hop" and "Hip Hop" share a page; the
+/// most common spelling names it. Albums and artists belong to a term when
+/// any of their tracks does. Terms come back sorted by name.
+pub fn collect<'a, F>(library: &'a Library, field: F) -> Vec<Term<'a>>
+where
+    F: Fn(&Track) -> Vec<&str>,
+{
+    let mut terms: BTreeMap<String, Term<'a>> = BTreeMap::new();
+    let mut spellings: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+
+    let mut add = |artist: &'a Artist, album: Option<&'a Album>, track: &'a Track, value: &str| {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+
+        let slug = term_slug(value);
+        *spellings
+            .entry(slug.clone())
+            .or_default()
+            .entry(value.to_string())
+            .or_default() += 1;
+
+        let term = terms.entry(slug.clone()).or_insert_with(|| Term {
+            name: String::new(),
+            slug,
+            artists: Vec::new(),
+            albums: Vec::new(),
+            tracks: Vec::new(),
+        });
+
+        if !term.artists.iter().any(|a| std::ptr::eq(*a, artist)) {
+            term.artists.push(artist);
+        }
+        if let Some(album) = album
+            && !term.albums.iter().any(|(_, a)| std::ptr::eq(*a, album))
+        {
+            term.albums.push((artist, album));
+        }
+        // A value repeated within one field still lists the track once
+        if !term
+            .tracks
+            .last()
+            .is_some_and(|(_, _, t)| std::ptr::eq(*t, track))
+        {
+            term.tracks.push((artist, album, track));
+        }
+    };
+
+    for artist in library.values() {
+        for album in &artist.albums {
+            for track in &album.tracks {
+                for value in field(track) {
+                    add(artist, Some(album), track, value);
+                }
+            }
+        }
+        for track in &artist.tracks {
+            for value in field(track) {
+                add(artist, None, track, value);
+            }
+        }
+    }
+
+    let mut terms: Vec<Term<'a>> = terms
+        .into_values()
+        .map(|mut term| {
+            term.name = most_common(&spellings[&term.slug]);
+            term
+        })
+        .collect();
+
+    terms.sort_by_key(|term| term.name.to_lowercase());
+    terms
+}
+
+/// A value of a game field (a store genre, a developer, ...) and the games
+/// that carry it.
+pub struct GameTerm<'a> {
+    pub name: String,
+    pub slug: String,
+    pub games: Vec<&'a Game>,
+}
+
+/// Group games by every value `field` returns for them, the way `collect`
+/// groups tracks. Games keep their library order within a term.
+pub fn collect_games<'a, F>(library: &'a GameLibrary, field: F) -> Vec<GameTerm<'a>>
+where
+    F: Fn(&Game) -> Vec<&str>,
+{
+    let mut terms: BTreeMap<String, GameTerm<'a>> = BTreeMap::new();
+    let mut spellings: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+
+    for game in library {
+        for value in field(game) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            let slug = term_slug(value);
+            *spellings
+                .entry(slug.clone())
+                .or_default()
+                .entry(value.to_string())
+                .or_default() += 1;
+
+            let term = terms.entry(slug.clone()).or_insert_with(|| GameTerm {
+                name: String::new(),
+                slug,
+                games: Vec::new(),
+            });
+            if !term.games.iter().any(|g| std::ptr::eq(*g, game)) {
+                term.games.push(game);
+            }
+        }
+    }
+
+    let mut terms: Vec<GameTerm<'a>> = terms
+        .into_values()
+        .map(|mut term| {
+            term.name = most_common(&spellings[&term.slug]);
+            term
+        })
+        .collect();
+
+    terms.sort_by_key(|term| term.name.to_lowercase());
+    terms
+}
+
+// Most common spelling wins, ties go to the first alphabetically
+fn most_common(spellings: &BTreeMap<String, usize>) -> String {
+    spellings
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(name, _)| name.clone())
+        .unwrap_or_default()
+}
+
+/// Page slug for a term value.
+pub fn term_slug(value: &str) -> String {
+    let slug = slugify(value.trim());
+    if slug.is_empty() {
+        "unknown".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: &str, title: &str, album: &str, genre: &str) -> Track {
+        Track {
+            id: id.to_string(),
+            title: title.to_string(),
+            artist: "toe".to_string(),
+            album: album.to_string(),
+            genre: genre.to_string(),
+            ..Track::default()
+        }
+    }
+
+    #[test]
+    fn merges_case_and_spelling_by_slug() {
+        let mut album = Album::new("1".to_string(), "Past".to_string(), "toe".to_string());
+        album.add_track(track("1", "Theme", "Past", "Post-Rock"));
+        album.add_track(track("2", "Interlude", "Past", "post rock"));
+        album.add_track(track("3", "Outro", "Past", " Post-Rock "));
+        album.add_track(track("4", "Coda", "Past", "Math Rock"));
+        let mut toe = Artist::new("toe".to_string());
+        toe.add_album(album);
+        toe.add_track(track("5", "Demo", "", "POST ROCK"));
+        let library = Library::from([("toe".to_string(), toe)]);
+
+        let terms = collect(&library, |track| vec![&track.genre]);
+
+        let summary: Vec<(&str, &str, usize, usize, usize)> = terms
+            .iter()
+            .map(|term| {
+                (
+                    term.name.as_str(),
+                    term.slug.as_str(),
+                    term.artists.len(),
+                    term.albums.len(),
+                    term.tracks.len(),
+                )
+            })
+            .collect();
+        // The most common spelling names the term
+        assert_eq!(
+            summary,
+            [
+                ("Math Rock", "math-rock", 1, 1, 1),
+                ("Post-Rock", "post-rock", 1, 1, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_empty_values_and_breaks_spelling_ties_alphabetically() {
+        let mut toe = Artist::new("toe".to_string());
+        toe.add_track(track("1", "A", "", ""));
+        toe.add_track(track("2", "B", "", "   "));
+        toe.add_track(track("3", "C", "", "hip hop"));
+        toe.add_track(track("4", "D", "", "Hip-Hop"));
+        let library = Library::from([("toe".to_string(), toe)]);
+
+        let terms = collect(&library, |track| vec![&track.genre]);
+
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].name, "Hip-Hop");
+        assert_eq!(terms[0].slug, "hip-hop");
+        assert_eq!(terms[0].albums.len(), 0);
+        assert_eq!(term_slug("!!!"), "unknown");
+    }
+
+    #[test]
+    fn splits_credits_on_semicolons_only() {
+        assert_eq!(credits("A; B"), ["A", "B"]);
+        assert_eq!(
+            credits("Lennon, John; McCartney, Paul"),
+            ["Lennon, John", "McCartney, Paul"]
+        );
+        assert_eq!(credits("AC/DC"), ["AC/DC"]);
+        assert_eq!(credits(" A ;; "), ["A"]);
+        assert!(credits("").is_empty());
+    }
+}
@@ -1,25 +1,27 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Track {
     pub id: String,
     pub title: String,
     pub artist: String,
     pub album: String,
     pub albumartist: String,
-    pub year: String,
+    pub year: Option<u32>,
     pub genre: String,
-    pub length: String,
-    pub track: String,
-    pub tracktotal: String,
-    pub disc: String,
-    pub disctotal: String,
-    pub bitrate: String,
+    pub length: Option<Duration>,
+    pub track: Option<u32>,
+    pub tracktotal: Option<u32>,
+    pub disc: Option<u32>,
+    pub disctotal: Option<u32>,
+    pub bitrate: Option<u32>, // kbps
     pub format: String,
     pub path: String,
     pub added: String,
     pub comments: String,
-    pub bpm: String,
+    pub bpm: Option<u32>,
     pub composer: String,
     pub label: String,
     pub country: String,
@@ -40,7 +42,426 @@ impl Track {
         !self.album.is_empty()
     }
 
-    pub fn track_number(&self) -> u32 {
-        self.track.parse().unwrap_or(0)
+    /// Length as "m:ss" (or "h:mm:ss"), empty when unknown.
+    pub fn length_display(&self) -> String {
+        let Some(length) = self.length else {
+            return String::new();
+        };
+        let seconds = length.as_secs_f64().round() as u64;
+        if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds % 3600 / 60,
+                seconds % 60
+            )
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        }
+    }
+
+    /// Bitrate as "320kbps", empty when unknown.
+    pub fn bitrate_display(&self) -> String {
+        self.bitrate
+            .map(|kbps| format!("{}kbps", kbps))
+            .unwrap_or_default()
+    }
+}
+
+/// A track record as `beet export` writes it, with every field a string.
+///
+/// Numbers and nulls are accepted too, so hand-edited or differently
+/// formatted exports still load; `into_track` does the actual validation.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RawTrack {
+    pub id: LenientString,
+    pub title: LenientString,
+    pub artist: LenientString,
+    pub album: LenientString,
+    pub albumartist: LenientString,
+    pub year: LenientString,
+    pub genre: LenientString,
+    pub length: LenientString,
+    pub track: LenientString,
+    pub tracktotal: LenientString,
+    pub disc: LenientString,
+    pub disctotal: LenientString,
+    pub bitrate: LenientString,
+    pub format: LenientString,
+    pub path: LenientString,
+    pub added: LenientString,
+    pub comments: LenientString,
+    pub bpm: LenientString,
+    pub composer: LenientString,
+    pub label: LenientString,
+    pub country: LenientString,
+    pub albumtype: LenientString,
+    pub mb_trackid: LenientString,
+    pub mb_albumid: LenientString,
+    pub mb_artistid: LenientString,
+    pub album_id: LenientString,
+}
+
+impl RawTrack {
+    /// Convert to a typed `Track`, recording a warning for every non-empty
+    /// field that can't be parsed instead of silently defaulting it.
+    pub fn into_track(self, record: usize, report: &mut TrackReport) -> Track {
+        let mut warn = |field: &'static str, value: &str, expected: &str| {
+            report.warnings.push(TrackWarning {
+                record,
+                id: self.id.to_string(),
+                title: self.title.to_string(),
+                message: format!("{} {:?} is not {}", field, value, expected),
+            });
+        };
+
+        let (track, track_of) = parse_position(&self.track).unwrap_or_else(|| {
+            warn("track", &self.track, "a track number");
+            (None, None)
+        });
+        let (disc, disc_of) = parse_position(&self.disc).unwrap_or_else(|| {
+            warn("disc", &self.disc, "a disc number");
+            (None, None)
+        });
+        let tracktotal = parse_number(&self.tracktotal)
+            .unwrap_or_else(|| {
+                warn("tracktotal", &self.tracktotal, "a number");
+                None
+            })
+            .or(track_of);
+        let disctotal = parse_number(&self.disctotal)
+            .unwrap_or_else(|| {
+                warn("disctotal", &self.disctotal, "a number");
+                None
+            })
+            .or(disc_of);
+        let year = parse_year(&self.year).unwrap_or_else(|| {
+            warn("year", &self.year, "a year");
+            None
+        });
+        let length = parse_length(&self.length).unwrap_or_else(|| {
+            warn("length", &self.length, "a duration");
+            None
+        });
+        let bitrate = parse_bitrate(&self.bitrate).unwrap_or_else(|| {
+            warn("bitrate", &self.bitrate, "a bitrate");
+            None
+        });
+        let bpm = parse_number(&self.bpm).unwrap_or_else(|| {
+            warn("bpm", &self.bpm, "a number");
+            None
+        });
+
+        Track {
+            id: self.id.into(),
+            title: self.title.into(),
+            artist: self.artist.into(),
+            album: self.album.into(),
+            albumartist: self.albumartist.into(),
+            year,
+            genre: self.genre.into(),
+            length,
+            track,
+            tracktotal,
+            disc,
+            disctotal,
+            bitrate,
+            format: self.format.into(),
+            path: self.path.into(),
+            added: self.added.into(),
+            comments: self.comments.into(),
+            bpm,
+            composer: self.composer.into(),
+            label: self.label.into(),
+            country: self.country.into(),
+            albumtype: self.albumtype.into(),
+            mb_trackid: self.mb_trackid.into(),
+            mb_albumid: self.mb_albumid.into(),
+            mb_artistid: self.mb_artistid.into(),
+            album_id: self.album_id.into(),
+        }
+    }
+}
+
+/// Convert exported JSON records to tracks, skipping records that aren't
+/// track objects at all.
+pub fn from_json_records(records: Vec<serde_json::Value>, report: &mut TrackReport) -> Vec<Track> {
+    let mut tracks = Vec::with_capacity(records.len());
+
+    for (record, value) in records.into_iter().enumerate() {
+        match serde_json::from_value::<RawTrack>(value) {
+            Ok(raw) => tracks.push(raw.into_track(record, report)),
+            Err(e) => report.warnings.push(TrackWarning {
+                record,
+                id: String::new(),
+                title: String::new(),
+                message: format!("skipped: {}", e),
+            }),
+        }
+    }
+
+    tracks
+}
+
+/// Problems found while reading track records.
+#[derive(Debug, Default)]
+pub struct TrackReport {
+    pub warnings: Vec<TrackWarning>,
+}
+
+impl TrackReport {
+    pub fn print(&self) {
+        if self.warnings.is_empty() {
+            return;
+        }
+
+        eprintln!("{} track warnings:", self.warnings.len());
+        for warning in &self.warnings {
+            eprintln!("  {}", warning);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TrackWarning {
+    pub record: usize,
+    pub id: String,
+    pub title: String,
+    pub message: String,
+}
+
+impl fmt::Display for TrackWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "record {}", self.record)?;
+        if !self.id.is_empty() {
+            write!(f, " (id {})", self.id)?;
+        }
+        if !self.title.is_empty() {
+            write!(f, " {:?}", self.title)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+// Each parser returns `None` for malformed input and `Some(None)` for an
+// empty or zero value, which beets uses to mean "unset".
+
+pub fn parse_number(value: &str) -> Option<Option<u32>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+    match value.parse::<u32>() {
+        Ok(0) => Some(None),
+        Ok(n) => Some(Some(n)),
+        Err(_) => None,
+    }
+}
+
+/// Track or disc position, also accepting the ID3 "3/12" form.
+pub fn parse_position(value: &str) -> Option<(Option<u32>, Option<u32>)> {
+    match value.split_once('/') {
+        Some((number, total)) => Some((parse_number(number)?, parse_number(total)?)),
+        None => Some((parse_number(value)?, None)),
+    }
+}
+
+/// Year from "2022", or from a full date such as "2022-03-01", "2022/03" or
+/// "2022-03-01T00:00:00Z", so tracks tagged either way group together.
+pub fn parse_year(value: &str) -> Option<Option<u32>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+
+    let digits = value.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = &value[digits..];
+    if digits != 4 || !(rest.is_empty() || rest.starts_with(['-', '/', '.', 'T', ' '])) {
+        return None;
+    }
+    parse_number(&value[..4])
+}
+
+/// Length as "m:ss", "h:mm:ss" or plain seconds.
+pub fn parse_length(value: &str) -> Option<Option<Duration>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+
+    let mut seconds = 0.0;
+    for part in value.split(':') {
+        let part: f64 = part.parse().ok()?;
+        if !part.is_finite() || part < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + part;
+    }
+
+    if seconds == 0.0 {
+        Some(None)
+    } else {
+        // Finite but too long for a Duration is as unparseable as text
+        Duration::try_from_secs_f64(seconds).ok().map(Some)
+    }
+}
+
+/// Bitrate in kbps from "320kbps" or "320 kbps", or from bits per second
+/// as "320000bps" or a bare "320000": beets stores bits per second, so a
+/// number without a unit is always read as that. Bitrates under 1kbps can't
+/// be told apart from a missing unit and are rejected.
+pub fn parse_bitrate(value: &str) -> Option<Option<u32>> {
+    let value = value.trim();
+    if let Some(kbps) = value.strip_suffix("kbps") {
+        return parse_number(kbps);
+    }
+    let bps = value.strip_suffix("bps").unwrap_or(value);
+    match parse_number(bps)? {
+        Some(bps) if bps < 500 => None,
+        bps => Some(bps.map(|bps| (bps + 500) / 1000)),
+    }
+}
+
+/// A string field that also accepts numbers, booleans and null.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LenientString(String);
+
+impl<'de> Deserialize<'de> for LenientString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self(match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        }))
+    }
+}
+
+impl std::ops::Deref for LenientString {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl From<String> for LenientString {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<LenientString> for String {
+    fn from(s: LenientString) -> Self {
+        s.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_years_from_dates() {
+        assert_eq!(parse_year("2012"), Some(Some(2012)));
+        assert_eq!(parse_year(" 2022-03-01 "), Some(Some(2022)));
+        assert_eq!(parse_year("2022/03"), Some(Some(2022)));
+        assert_eq!(parse_year("2022-03-01T00:00:00Z"), Some(Some(2022)));
+        assert_eq!(parse_year(""), Some(None));
+        assert_eq!(parse_year("0000"), Some(None));
+        assert_eq!(parse_year("12"), None);
+        assert_eq!(parse_year("20221"), None);
+        assert_eq!(parse_year("2022a"), None);
+        assert_eq!(parse_year("unknown"), None);
+    }
+
+    #[test]
+    fn parses_positions_and_numbers() {
+        assert_eq!(parse_number(" 7 "), Some(Some(7)));
+        assert_eq!(parse_number("0"), Some(None));
+        assert_eq!(parse_number(""), Some(None));
+        assert_eq!(parse_number("-1"), None);
+        assert_eq!(parse_number("seven"), None);
+
+        assert_eq!(parse_position("3"), Some((Some(3), None)));
+        assert_eq!(parse_position("3/12"), Some((Some(3), Some(12))));
+        assert_eq!(parse_position("3/"), Some((Some(3), None)));
+        assert_eq!(parse_position("A1"), None);
+        assert_eq!(parse_position("3/x"), None);
+    }
+
+    #[test]
+    fn parses_lengths() {
+        let secs = |value: &str| parse_length(value).map(|d| d.map(|d| d.as_secs_f64()));
+        assert_eq!(secs("2:53"), Some(Some(173.0)));
+        assert_eq!(secs("1:02:03"), Some(Some(3723.0)));
+        assert_eq!(secs("173.5"), Some(Some(173.5)));
+        assert_eq!(secs("0:00"), Some(None));
+        assert_eq!(secs(""), Some(None));
+        assert_eq!(secs("2:-1"), None);
+        assert_eq!(secs("NaN"), None);
+        assert_eq!(secs("1e300"), None);
+        assert_eq!(secs("long"), None);
+    }
+
+    #[test]
+    fn parses_bitrates_as_kbps_or_bits_per_second() {
+        assert_eq!(parse_bitrate("320kbps"), Some(Some(320)));
+        assert_eq!(parse_bitrate("320 kbps"), Some(Some(320)));
+        assert_eq!(parse_bitrate("320000bps"), Some(Some(320)));
+        // Bare numbers are bits per second, as beets stores them
+        assert_eq!(parse_bitrate("320000"), Some(Some(320)));
+        assert_eq!(parse_bitrate("7999"), Some(Some(8)));
+        assert_eq!(parse_bitrate("1411200"), Some(Some(1411)));
+        assert_eq!(parse_bitrate("0"), Some(None));
+        assert_eq!(parse_bitrate(""), Some(None));
+        // Almost certainly kbps missing its unit
+        assert_eq!(parse_bitrate("320"), None);
+        assert_eq!(parse_bitrate("fast"), None);
+    }
+
+    #[test]
+    fn converts_lenient_records_with_warnings() {
+        let records = vec![
+            serde_json::json!({
+                "id": 12,
+                "title": "Goodbye",
+                "artist": "toe",
+                "track": "3/12",
+                "disc": 1,
+                "year": "2009-04-01",
+                "length": 253.4,
+                "bitrate": 320000,
+                "bpm": "fast",
+                "comments": null,
+            }),
+            serde_json::json!("not a track"),
+        ];
+        let mut report = TrackReport::default();
+
+        let tracks = from_json_records(records, &mut report);
+
+        assert_eq!(tracks.len(), 1);
+        let track = &tracks[0];
+        assert_eq!(track.id, "12");
+        assert_eq!((track.track, track.tracktotal), (Some(3), Some(12)));
+        assert_eq!((track.disc, track.disctotal), (Some(1), None));
+        assert_eq!(track.year, Some(2009));
+        assert_eq!(track.length_display(), "4:13");
+        assert_eq!(track.bitrate_display(), "320kbps");
+        assert_eq!(track.bpm, None);
+        assert_eq!(track.comments, "");
+
+        let warnings: Vec<String> = report.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            [
+                r#"record 0 (id 12) "Goodbye": bpm "fast" is not a number"#,
+                "record 1: skipped: invalid type: string \"not a track\", expected struct RawTrack",
+            ]
+        );
     }
 }
@@ -0,0 +1,333 @@
+//! Valve's KeyValues ("VDF") format, as used by Steam's local config files,
+//! in both its text form and the binary form of `shortcuts.vdf`.
+
+use anyhow::Result;
+
+/// A KeyValues value: a string, or a nested block of entries.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Block(Block),
+}
+
+/// Entries of a block in file order. Keys may repeat and are matched
+/// case-insensitively, since Steam isn't consistent about their case.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Block(pub Vec<(String, Value)>);
+
+impl Block {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Value::String(s) => Some(s),
+            Value::Block(_) => None,
+        }
+    }
+
+    pub fn block(&self, key: &str) -> Option<&Block> {
+        match self.get(key)? {
+            Value::Block(block) => Some(block),
+            Value::String(_) => None,
+        }
+    }
+
+    /// A string value parsed as a number, `None` if missing or malformed.
+    pub fn number(&self, key: &str) -> Option<u64> {
+        self.str(key)?.trim().parse().ok()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+/// Parse binary KeyValues, e.g. `shortcuts.vdf`. Numbers are kept as their
+/// decimal strings so both forms read the same; 32-bit integers are treated
+/// as unsigned, which is what Steam's app IDs are.
+pub fn parse_binary(input: &[u8]) -> Result<Block> {
+    let mut reader = BinaryReader { input, pos: 0 };
+    let block = reader.block(false)?;
+    if reader.pos != input.len() {
+        return Err(anyhow::anyhow!(
+            "unexpected end of block at byte {}",
+            reader.pos
+        ));
+    }
+    Ok(block)
+}
+
+// Type bytes preceding each binary entry
+const BINARY_BLOCK: u8 = 0x00;
+const BINARY_STRING: u8 = 0x01;
+const BINARY_INT32: u8 = 0x02;
+const BINARY_FLOAT32: u8 = 0x03;
+const BINARY_UINT64: u8 = 0x07;
+const BINARY_END: u8 = 0x08;
+
+struct BinaryReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl BinaryReader<'_> {
+    // Reads entries up to the end marker of the block. The root's marker
+    // is optional, so it may run to the end of input instead.
+    fn block(&mut self, nested: bool) -> Result<Block> {
+        let mut block = Block::default();
+
+        while let Some(kind) = self.input.get(self.pos).copied() {
+            self.pos += 1;
+            if kind == BINARY_END {
+                return Ok(block);
+            }
+
+            let key = self.string()?;
+            let value = match kind {
+                BINARY_BLOCK => Value::Block(self.block(true)?),
+                BINARY_STRING => Value::String(self.string()?),
+                BINARY_INT32 => Value::String(u32::from_le_bytes(self.bytes()?).to_string()),
+                BINARY_FLOAT32 => Value::String(f32::from_le_bytes(self.bytes()?).to_string()),
+                BINARY_UINT64 => Value::String(u64::from_le_bytes(self.bytes()?).to_string()),
+                kind => {
+                    return Err(anyhow::anyhow!(
+                        "unknown type {:#04x} for '{}' at byte {}",
+                        kind,
+                        key,
+                        self.pos
+                    ));
+                }
+            };
+            block.0.push((key, value));
+        }
+
+        if nested {
+            return Err(anyhow::anyhow!("unclosed block at end of input"));
+        }
+        Ok(block)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow::anyhow!("unterminated string at byte {}", self.pos))?;
+        self.pos += len + 1;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .input
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| anyhow::anyhow!("truncated number at byte {}", self.pos))?;
+        self.pos += N;
+        Ok(bytes.try_into().expect("slice has length N"))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    Open,
+    Close,
+}
+
+/// Parse text KeyValues, e.g. `appmanifest_*.acf` or `libraryfolders.vdf`.
+/// The whole file is returned as one block, so the root key is its only entry.
+pub fn parse_text(input: &str) -> Result<Block> {
+    let tokens = tokenize(input)?;
+    let mut tokens = tokens.into_iter().peekable();
+    let block = parse_block(&mut tokens)?;
+
+    match tokens.next() {
+        None => Ok(block),
+        Some((line, _)) => Err(anyhow::anyhow!("unexpected '}}' on line {}", line)),
+    }
+}
+
+fn parse_block(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<(usize, Token)>>,
+) -> Result<Block> {
+    let mut block = Block::default();
+
+    // A closing brace ends this block and is left for the caller
+    while let Some((line, token)) = tokens.next_if(|(_, token)| *token != Token::Close) {
+        let key = match token {
+            Token::String(key) => key,
+            _ => return Err(anyhow::anyhow!("unexpected '{{' on line {}", line)),
+        };
+
+        let value = match tokens.next() {
+            Some((_, Token::String(value))) => Value::String(value),
+            Some((_, Token::Open)) => {
+                let nested = parse_block(tokens)?;
+                match tokens.next() {
+                    Some((_, Token::Close)) => Value::Block(nested),
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "unclosed block '{}' from line {}",
+                            key,
+                            line
+                        ));
+                    }
+                }
+            }
+            Some((line, Token::Close)) => {
+                return Err(anyhow::anyhow!(
+                    "missing value for '{}' on line {}",
+                    key,
+                    line
+                ));
+            }
+            None => {
+                return Err(anyhow::anyhow!(
+                    "missing value for '{}' at end of file",
+                    key
+                ));
+            }
+        };
+        block.0.push((key, value));
+    }
+
+    Ok(block)
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '{' => tokens.push((line, Token::Open)),
+            '}' => tokens.push((line, Token::Close)),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            // Platform conditionals such as [$WIN32] apply to the entry
+            // before them; every entry is kept regardless
+            '[' => {
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(c) => s.push(c),
+                            None => break,
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            s.push(c);
+                        }
+                        None => {
+                            return Err(anyhow::anyhow!("unterminated string on line {}", start));
+                        }
+                    }
+                }
+                tokens.push((start, Token::String(s)));
+            }
+            c => {
+                let mut s = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push((line, Token::String(s)));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_blocks() {
+        let input = r#"
+"AppState"
+{
+	"appid"		"413150"
+	"name"		"Stardew \"Valley\""
+	// a comment
+	"UserConfig"
+	{
+		"language"		"english"
+	}
+	unquoted	value
+}
+"#;
+        let root = parse_text(input).unwrap();
+        let app = root.block("appstate").unwrap();
+
+        assert_eq!(app.number("appid"), Some(413150));
+        assert_eq!(app.str("name"), Some("Stardew \"Valley\""));
+        assert_eq!(
+            app.block("userconfig").unwrap().str("language"),
+            Some("english")
+        );
+        assert_eq!(app.str("unquoted"), Some("value"));
+    }
+
+    #[test]
+    fn reports_malformed_input() {
+        assert!(parse_text("\"a\" {").is_err());
+        assert!(parse_text("\"a\" \"b\" }").is_err());
+        assert!(parse_text("\"a\"").is_err());
+        assert!(parse_text("\"a").is_err());
+    }
+
+    #[test]
+    fn parses_binary_blocks() {
+        let mut input = Vec::new();
+        input.extend(b"\x00shortcuts\x00\x000\x00");
+        input.extend(b"\x02appid\x00");
+        input.extend(3_000_000_000u32.to_le_bytes());
+        input.extend(b"\x01AppName\x00Doom\x00");
+        input.extend(b"\x00tags\x00\x08");
+        input.extend(b"\x08\x08\x08");
+
+        let root = parse_binary(&input).unwrap();
+        let shortcut = root.block("shortcuts").unwrap().block("0").unwrap();
+
+        assert_eq!(shortcut.number("appid"), Some(3_000_000_000));
+        assert_eq!(shortcut.str("appname"), Some("Doom"));
+        assert_eq!(shortcut.block("tags"), Some(&Block::default()));
+
+        assert!(parse_binary(b"\x02appid\x00\x01\x00").is_err());
+        assert!(parse_binary(b"\x09key\x00").is_err());
+        assert!(parse_binary(b"\x08\x08").is_err());
+        assert!(parse_binary(b"\x00shortcuts\x00").is_err());
+    }
+}